@@ -1,6 +1,6 @@
 use std::error::Error;
 
-use common_utils::{image::ImageRequest, palette::generate_n_random_palettes};
+use common_utils::{image::ImageRequest, generator::{load_generator, PaletteGenerator, RandomV1}};
 use image::DynamicImage;
 use image_effects::{
     prelude::*, dither::bayer::Bayer,
@@ -12,6 +12,12 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     const TARGET: &str = "https://images.enbyss.com/_/gallery/eye-of-mine.png";
 
+    // optionally, a palette config (as YAML) can be passed - random_v1 is used otherwise.
+    let generator: Box<dyn PaletteGenerator> = match std::env::args().nth(1) {
+        Some(path) => load_generator(path)?,
+        None => Box::new(RandomV1),
+    };
+
     let image = ImageRequest::new(TARGET.into())
         .image()
         .url()
@@ -22,7 +28,7 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     image.save("./basic/data/__SOURCE.png")?;
 
-    generate_images_with_n_random_palettes(image, 500)?;
+    generate_images_with_n_random_palettes(image, generator.as_ref(), 500)?;
     // generate_images_with_predetermined_palettes(image, common_utils::palette::palettes())?;
 
     // let gradient = generate_hue_gradient(230.0);
@@ -31,9 +37,11 @@ fn main() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
-fn generate_images_with_n_random_palettes(image: DynamicImage, n: usize) -> Result<(), Box<dyn Error>> {
+fn generate_images_with_n_random_palettes(image: DynamicImage, generator: &dyn PaletteGenerator, n: usize) -> Result<(), Box<dyn Error>> {
     let mut rng = StdRng::from_entropy();
-    let palettes = generate_n_random_palettes(&mut rng, n);
+    let palettes = (0..n)
+        .map(|_| generator.generate_logged(&mut rng))
+        .collect::<Result<Vec<_>, _>>()?;
 
     for (i, (palette, log)) in palettes.into_iter().enumerate() {
         println!("palette {i} / {n}");
@@ -0,0 +1,187 @@
+use palette::{color_difference::Ciede2000, named, Hsl, IntoColor, Lab, Lch, Oklab, Oklch, Srgb};
+use serde_yaml::Value;
+
+/// How the difference between two colours is measured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeltaMetric {
+    Ciede2000,
+    /// The euclidean distance in Oklab, scaled by 100 so it's comparable to ciede2000.
+    Oklab,
+}
+
+impl DeltaMetric {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "ciede2000" => Some(DeltaMetric::Ciede2000),
+            "oklab" => Some(DeltaMetric::Oklab),
+            _ => None,
+        }
+    }
+
+    pub fn delta(&self, a: Srgb, b: Srgb) -> f32 {
+        match self {
+            DeltaMetric::Ciede2000 => {
+                let (a, b): (Lab, Lab) = (a.into_color(), b.into_color());
+                a.difference(b)
+            }
+            DeltaMetric::Oklab => {
+                let (a, b): (Oklab, Oklab) = (a.into_color(), b.into_color());
+                100.0 * ((a.l - b.l).powi(2) + (a.a - b.a).powi(2) + (a.b - b.b).powi(2)).sqrt()
+            }
+        }
+    }
+}
+
+// COLOUR LITERALS
+// ===============
+// Colours written in a config - such as imgtoy's [rgb], or a colour on its own - can be any of:
+//  - a hexcode, with or without a `#`: `#rgb`, `#rrggbb` or `#rrggbbaa`. alpha is ignored.
+//      (remember to quote any starting with `#`, or YAML will read them as a comment)
+//  - a CSS colour name, like `rebeccapurple` - these are also the palette crate's `named::*`
+//      constants, so `named::REBECCAPURPLE` works too.
+//  - `hsl(h, s%, l%)`, `lab(l, a, b)`, `lch(l, c, h)` or `oklch(l, c, h)`. components can be
+//      split by commas or spaces, and percentages are allowed. (for oklch, `l` is 0-1 or a %.
+//      for hsl, `s` and `l` are always percentages - `hsl(120, 50, 50)` is `hsl(120, 50%, 50%)`)
+//  - a list of 3 components, either floats (0-1) or integers (0-255).
+
+/// Parses a single colour literal, returning a description of the problem if it isn't valid.
+pub fn parse_rgb(value: &Value) -> Result<Srgb, String> {
+    match value {
+        Value::String(literal) => parse_colour_literal(literal.trim()),
+        // unquoted hexcodes made of only digits (like 000000) are read by YAML as numbers.
+        Value::Number(number) if number.as_u64().is_some_and(|n| n <= 999_999) => {
+            parse_colour_literal(&format!("{:06}", number.as_u64().unwrap()))
+        }
+        Value::Sequence(components) => {
+            if components.len() != 3 {
+                return Err(format!(
+                    "there should be exactly 3 RGB components, found {}.",
+                    components.len()
+                ));
+            }
+
+            if components.iter().all(|c| c.is_u64()) {
+                let components = components
+                    .iter()
+                    .map(|c| c.as_u64().unwrap().min(255) as u8)
+                    .collect::<Vec<_>>();
+                Ok(Srgb::<u8>::new(components[0], components[1], components[2]).into_format())
+            } else if components.iter().all(|c| c.is_f64() || c.is_u64()) {
+                let components = components
+                    .iter()
+                    .map(|c| c.as_f64().unwrap() as f32)
+                    .collect::<Vec<_>>();
+                Ok(Srgb::new(components[0], components[1], components[2]))
+            } else {
+                Err(format!(
+                    "RGB components must all be numbers, found {components:?}."
+                ))
+            }
+        }
+        value => Err(format!(
+            "expected a colour string or a list of 3 components, found {value:?}."
+        )),
+    }
+}
+
+fn parse_colour_literal(literal: &str) -> Result<Srgb, String> {
+    let lowercase = literal.to_lowercase();
+
+    if let Some((function, args)) = lowercase
+        .strip_suffix(')')
+        .and_then(|inner| inner.split_once('('))
+    {
+        let args = args
+            .split(|c: char| c == ',' || c == '/' || c.is_whitespace())
+            .filter(|arg| !arg.is_empty())
+            .collect::<Vec<_>>();
+        return parse_colour_function(function.trim(), &args)
+            .map_err(|err| format!("[{literal}] {err}"));
+    }
+
+    let name = lowercase.strip_prefix("named::").unwrap_or(&lowercase);
+    if let Some(colour) = named::from_str(name) {
+        return Ok(colour.into_format());
+    }
+
+    let hex = literal.strip_prefix('#').unwrap_or(literal);
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!(
+            "[{literal}] is not a hexcode, a known colour name, or a colour function."
+        ));
+    }
+
+    let digits = hex
+        .chars()
+        .map(|c| c.to_digit(16).unwrap() as u8)
+        .collect::<Vec<_>>();
+    let (r, g, b) = match digits.len() {
+        3 => (digits[0] * 17, digits[1] * 17, digits[2] * 17),
+        6 | 8 => (
+            digits[0] * 16 + digits[1],
+            digits[2] * 16 + digits[3],
+            digits[4] * 16 + digits[5],
+        ),
+        n => {
+            return Err(format!(
+                "[{literal}] has {n} hex digits - hexcodes must be #rgb, #rrggbb or #rrggbbaa."
+            ))
+        }
+    };
+
+    Ok(Srgb::new(r, g, b).into_format())
+}
+
+fn parse_colour_function(function: &str, args: &[&str]) -> Result<Srgb, String> {
+    if args.len() < 3 {
+        return Err(format!(
+            "{function}() needs 3 components, found {}.",
+            args.len()
+        ));
+    }
+
+    // percentages are scaled to [percent_of], so 50% of 100 is 50.
+    let component = |i: usize, percent_of: f32| -> Result<f32, String> {
+        let arg = args[i].trim_end_matches("deg");
+        match arg.strip_suffix('%') {
+            Some(percent) => percent.parse::<f32>().map(|p| p / 100.0 * percent_of),
+            None => arg.parse::<f32>(),
+        }
+        .map_err(|_| format!("{} is not a valid number.", args[i]))
+    };
+
+    // hsl's saturation and lightness are percentages either way - `50` is the same as `50%`,
+    //  as in CSS.
+    let percentage = |i: usize| -> Result<f32, String> {
+        args[i]
+            .strip_suffix('%')
+            .unwrap_or(args[i])
+            .parse::<f32>()
+            .map(|p| p / 100.0)
+            .map_err(|_| format!("{} is not a valid number.", args[i]))
+    };
+
+    Ok(match function {
+        "hsl" => Hsl::new(component(0, 360.0)?, percentage(1)?, percentage(2)?).into_color(),
+        "lab" => Lab::new(
+            component(0, 100.0)?,
+            component(1, 125.0)?,
+            component(2, 125.0)?,
+        )
+        .into_color(),
+        "lch" => Lch::new(
+            component(0, 100.0)?,
+            component(1, 150.0)?,
+            component(2, 360.0)?,
+        )
+        .into_color(),
+        "oklch" => {
+            Oklch::new(component(0, 1.0)?, component(1, 0.4)?, component(2, 360.0)?).into_color()
+        }
+        _ => {
+            return Err(format!(
+                "{function}() is not a supported colour function - expected hsl, lab, lch or oklch."
+            ))
+        }
+    })
+}
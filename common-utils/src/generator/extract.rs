@@ -0,0 +1,416 @@
+use std::collections::HashMap;
+
+use image::{DynamicImage, GenericImageView};
+use palette::{Clamp, IntoColor, Lab, Oklab, Srgb};
+use rand::{seq::SliceRandom, Rng, RngCore};
+use serde_yaml::Value;
+
+use crate::image::{resize_image_with_max_dim, ImageRequest};
+
+use super::{yaml_str, yaml_u64, GeneratorResult, PaletteGenerator};
+
+// EXTRACTED PALETTES
+// ==================
+// The dominant colours of an image, found by one of:
+//  - kmeans        - k-means, initialised via k-means++.
+//  - median-cut    - boxes of colours are split along their widest component.
+//  - octree        - colours are bucketed into an octree, which is then folded down.
+//
+// Images are shrunk before extraction, and a random sample of their pixels is used,
+//  picked by the RNG - as is k-means' initialisation. So with a seeded RNG, extraction is
+//  reproducible.
+//
+// From YAML, [from] is a path or url to the image:
+//
+//      type: extract
+//      from: ./sunset.png
+//      count: 8
+//      method: kmeans      # kmeans, median-cut or octree.
+//      space: lab          # lab or oklab - which space colours are compared in.
+
+const MAX_DIM: usize = 256;
+const MAX_SAMPLES: usize = 8192;
+const KMEANS_ITERATIONS: usize = 24;
+
+#[derive(Debug, Clone, Copy)]
+pub enum ExtractMethod {
+    KMeans,
+    MedianCut,
+    Octree,
+}
+
+impl ExtractMethod {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "kmeans" => Some(ExtractMethod::KMeans),
+            "median-cut" => Some(ExtractMethod::MedianCut),
+            "octree" => Some(ExtractMethod::Octree),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum ExtractSpace {
+    Lab,
+    Oklab,
+}
+
+type Point = [f32; 3];
+
+impl ExtractSpace {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "lab" => Some(ExtractSpace::Lab),
+            "oklab" => Some(ExtractSpace::Oklab),
+            _ => None,
+        }
+    }
+
+    fn to_point(&self, colour: Srgb) -> Point {
+        match self {
+            ExtractSpace::Lab => {
+                let (l, a, b) = IntoColor::<Lab>::into_color(colour).into_components();
+                [l, a, b]
+            }
+            ExtractSpace::Oklab => {
+                let (l, a, b) = IntoColor::<Oklab>::into_color(colour).into_components();
+                [l, a, b]
+            }
+        }
+    }
+
+    fn to_srgb(&self, [l, a, b]: Point) -> Srgb {
+        let colour: Srgb = match self {
+            ExtractSpace::Lab => Lab::new(l, a, b).into_color(),
+            ExtractSpace::Oklab => Oklab::new(l, a, b).into_color(),
+        };
+        colour.clamp()
+    }
+
+    /// The rough extent of each component, used to fit colours into the octree.
+    fn bounds(&self) -> [(f32, f32); 3] {
+        match self {
+            ExtractSpace::Lab => [(0.0, 100.0), (-128.0, 128.0), (-128.0, 128.0)],
+            ExtractSpace::Oklab => [(0.0, 1.0), (-0.4, 0.4), (-0.4, 0.4)],
+        }
+    }
+}
+
+/// Extracts [count] colours from [pixels] - usually via `Extract::pixels_of`.
+#[derive(Debug, Clone)]
+pub struct Extract {
+    pub pixels: Vec<Srgb>,
+    pub count: usize,
+    pub method: ExtractMethod,
+    pub space: ExtractSpace,
+}
+
+impl Extract {
+    /// The opaque pixels of an image, once shrunk down for extraction.
+    pub fn pixels_of(image: &DynamicImage) -> Vec<Srgb> {
+        resize_image_with_max_dim(image, MAX_DIM)
+            .pixels()
+            .filter(|(_, _, pixel)| pixel.0[3] != 0)
+            .map(|(_, _, pixel)| {
+                let [r, g, b, _] = pixel.0;
+                Srgb::new(r, g, b).into_format()
+            })
+            .collect()
+    }
+
+    /// Loads an image from a path or url, and gives the pixels to extract from.
+    pub fn load_pixels(from: &str) -> GeneratorResult<Vec<Srgb>> {
+        let request = ImageRequest::new(from.into()).image();
+        let request = match from.starts_with("http://") || from.starts_with("https://") {
+            true => request.url(),
+            false => request.file(),
+        };
+        let image = request
+            .perform()
+            .and_then(|image| Ok(image.into_image()?))
+            .map_err(|err| format!("could not load [palette.from] image [{from}]: {err}"))?;
+
+        Ok(Extract::pixels_of(&image))
+    }
+
+    pub fn from_yaml(value: &Value) -> GeneratorResult<Self> {
+        let from =
+            yaml_str(value, "from")?.ok_or("[palette.from] must be a path/url to an image.")?;
+
+        let pixels = Extract::load_pixels(from)?;
+
+        let method = match yaml_str(value, "method")? {
+            None => ExtractMethod::KMeans,
+            Some(method) => ExtractMethod::from_name(method).ok_or_else(|| {
+                format!("{method} is not a valid extraction method - expected kmeans, median-cut or octree.")
+            })?,
+        };
+
+        let space = match yaml_str(value, "space")? {
+            None => ExtractSpace::Lab,
+            Some(space) => ExtractSpace::from_name(space).ok_or_else(|| {
+                format!("{space} is not a valid colour space - expected lab or oklab.")
+            })?,
+        };
+
+        Ok(Extract {
+            pixels,
+            count: yaml_u64(value, "count")?.unwrap_or(8) as usize,
+            method,
+            space,
+        })
+    }
+}
+
+impl PaletteGenerator for Extract {
+    fn generate(&self, rng: &mut dyn RngCore) -> GeneratorResult<Vec<Srgb>> {
+        if self.count == 0 {
+            return Err("[palette.count] must be at least 1.".into());
+        }
+        if self.pixels.is_empty() {
+            return Err("the image has no opaque pixels to extract from.".into());
+        }
+
+        let samples = self
+            .pixels
+            .choose_multiple(rng, MAX_SAMPLES)
+            .map(|colour| self.space.to_point(*colour))
+            .collect::<Vec<_>>();
+
+        let centres = match self.method {
+            ExtractMethod::KMeans => kmeans(rng, &samples, self.count),
+            ExtractMethod::MedianCut => median_cut(&samples, self.count),
+            ExtractMethod::Octree => octree(&samples, self.count, self.space.bounds()),
+        };
+
+        Ok(centres
+            .into_iter()
+            .map(|centre| self.space.to_srgb(centre))
+            .collect())
+    }
+}
+
+fn distance_sq(a: &Point, b: &Point) -> f32 {
+    (0..3).map(|i| (a[i] - b[i]).powi(2)).sum()
+}
+
+fn mean(points: &[&Point]) -> Point {
+    let mut sum = [0.0; 3];
+    for point in points {
+        (0..3).for_each(|i| sum[i] += point[i]);
+    }
+    sum.map(|component| component / points.len().max(1) as f32)
+}
+
+fn nearest(centres: &[Point], point: &Point) -> usize {
+    (0..centres.len())
+        .min_by(|&a, &b| {
+            distance_sq(&centres[a], point).total_cmp(&distance_sq(&centres[b], point))
+        })
+        .unwrap_or(0)
+}
+
+// K-MEANS
+// =======
+// Centres are initialised via k-means++ - each picked with a chance proportional to its
+//  squared distance from the nearest centre so far - and then refined.
+fn kmeans(rng: &mut (impl Rng + ?Sized), points: &[Point], k: usize) -> Vec<Point> {
+    let mut centres = vec![*points.choose(rng).unwrap()];
+
+    while centres.len() < k {
+        let weights = points
+            .iter()
+            .map(|point| distance_sq(&centres[nearest(&centres, point)], point))
+            .collect::<Vec<_>>();
+        let total = weights.iter().sum::<f32>();
+
+        // every remaining point is already a centre.
+        if total <= 0.0 {
+            break;
+        }
+
+        let mut target = rng.gen_range(0.0..total);
+        let picked = weights
+            .iter()
+            .position(|weight| {
+                target -= weight;
+                target <= 0.0
+            })
+            .unwrap_or(points.len() - 1);
+
+        centres.push(points[picked]);
+    }
+
+    for _ in 0..KMEANS_ITERATIONS {
+        let mut clusters = vec![vec![]; centres.len()];
+        for point in points {
+            clusters[nearest(&centres, point)].push(point);
+        }
+
+        let updated = clusters
+            .iter()
+            .zip(&centres)
+            .map(|(cluster, centre)| match cluster.is_empty() {
+                true => *centre,
+                false => mean(cluster),
+            })
+            .collect::<Vec<_>>();
+
+        if updated == centres {
+            break;
+        }
+        centres = updated;
+    }
+
+    centres
+}
+
+// MEDIAN CUT
+// ==========
+// The box with the widest range of any component is split at its median along it,
+//  until there are enough boxes. Each box's colour is its mean.
+fn median_cut(points: &[Point], count: usize) -> Vec<Point> {
+    let mut boxes = vec![points.iter().collect::<Vec<_>>()];
+
+    while boxes.len() < count {
+        let widest = boxes
+            .iter()
+            .enumerate()
+            .filter(|(_, points)| points.len() > 1)
+            .map(|(i, points)| {
+                let (axis, range) = (0..3)
+                    .map(|axis| {
+                        let (min, max) =
+                            points.iter().fold((f32::MAX, f32::MIN), |(min, max), p| {
+                                (min.min(p[axis]), max.max(p[axis]))
+                            });
+                        (axis, max - min)
+                    })
+                    .max_by(|a, b| a.1.total_cmp(&b.1))
+                    .unwrap();
+                (i, axis, range)
+            })
+            .max_by(|a, b| a.2.total_cmp(&b.2));
+
+        let Some((i, axis, range)) = widest else {
+            break;
+        };
+        if range <= 0.0 {
+            break;
+        }
+
+        let mut split = boxes.swap_remove(i);
+        split.sort_by(|a, b| a[axis].total_cmp(&b[axis]));
+        // split where the component changes nearest the median, so neither half is empty.
+        let middle = split.len() / 2;
+        let at = (1..split.len())
+            .filter(|&j| split[j - 1][axis] < split[j][axis])
+            .min_by_key(|&j| j.abs_diff(middle))
+            .unwrap_or(middle);
+        let upper = split.split_off(at);
+        boxes.push(split);
+        boxes.push(upper);
+    }
+
+    boxes.iter().map(|points| mean(points)).collect()
+}
+
+// OCTREE
+// ======
+// Colours are fit into an octree, 6 levels deep. Then the least populated nodes on the
+//  deepest level are folded into their parents, until there are few enough leaves.
+// Each leaf's colour is the mean of the colours within it.
+// Parents are skipped if folding them would leave too few colours - and if that leaves
+//  too many, the closest pairs of leaves are merged instead.
+const OCTREE_DEPTH: u32 = 6;
+
+fn octree(points: &[Point], count: usize, bounds: [(f32, f32); 3]) -> Vec<Point> {
+    // the path to each point's leaf, as its quantized components.
+    let key = |point: &Point, depth: u32| -> [u32; 3] {
+        [0, 1, 2].map(|i| {
+            let (min, max) = bounds[i];
+            let normalised = ((point[i] - min) / (max - min)).clamp(0.0, 1.0);
+            let quantized = (normalised * ((1 << OCTREE_DEPTH) - 1) as f32).round() as u32;
+            quantized >> (OCTREE_DEPTH - depth)
+        })
+    };
+
+    let mut leaves: HashMap<(u32, [u32; 3]), Vec<&Point>> = HashMap::new();
+    for point in points {
+        leaves
+            .entry((OCTREE_DEPTH, key(point, OCTREE_DEPTH)))
+            .or_default()
+            .push(point);
+    }
+
+    for depth in (1..=OCTREE_DEPTH).rev() {
+        if leaves.len() <= count {
+            break;
+        }
+
+        // nodes on this level, grouped by parent - least populated parents first.
+        let mut parents: HashMap<[u32; 3], (usize, Vec<[u32; 3]>)> = HashMap::new();
+        for ((leaf_depth, leaf_key), leaf_points) in &leaves {
+            if *leaf_depth == depth {
+                let parent = parents.entry(leaf_key.map(|k| k >> 1)).or_default();
+                parent.0 += leaf_points.len();
+                parent.1.push(*leaf_key);
+            }
+        }
+        let mut parents = parents.into_iter().collect::<Vec<_>>();
+        parents.sort_by_key(|(parent, (population, _))| (*population, *parent));
+
+        for (parent, (_, children)) in parents {
+            if leaves.len() <= count {
+                break;
+            }
+
+            // folding in a parent with many children could leave too few colours.
+            if leaves.len() - (children.len() - 1) < count {
+                continue;
+            }
+
+            let merged = children
+                .iter()
+                .flat_map(|child| leaves.remove(&(depth, *child)).unwrap_or_default())
+                .collect::<Vec<_>>();
+
+            leaves
+                .entry((depth - 1, parent))
+                .or_default()
+                .extend(merged);
+        }
+    }
+
+    // sorted, so the order doesn't depend on the map's.
+    let mut leaves = leaves.into_iter().collect::<Vec<_>>();
+    leaves.sort_by_key(|(key, _)| *key);
+
+    let mut leaves = leaves
+        .iter()
+        .map(|(_, points)| (mean(points), points.len()))
+        .collect::<Vec<_>>();
+
+    // any leaves that couldn't be folded are merged with their closest neighbour instead.
+    while leaves.len() > count {
+        let (a, b) = (0..leaves.len())
+            .flat_map(|a| (a + 1..leaves.len()).map(move |b| (a, b)))
+            .min_by(|&(a1, b1), &(a2, b2)| {
+                distance_sq(&leaves[a1].0, &leaves[b1].0)
+                    .total_cmp(&distance_sq(&leaves[a2].0, &leaves[b2].0))
+            })
+            .unwrap();
+
+        let (centre_b, weight_b) = leaves.remove(b);
+        let (centre_a, weight_a) = leaves[a];
+        let total = (weight_a + weight_b) as f32;
+        leaves[a] = (
+            [0, 1, 2]
+                .map(|i| (centre_a[i] * weight_a as f32 + centre_b[i] * weight_b as f32) / total),
+            weight_a + weight_b,
+        );
+    }
+
+    leaves.into_iter().map(|(centre, _)| centre).collect()
+}
@@ -0,0 +1,413 @@
+use std::path::{Path, PathBuf};
+
+use image::GenericImageView;
+use palette::{Clamp, IntoColor, Lab, Srgb};
+use rand::RngCore;
+use serde_yaml::Value;
+
+use super::{yaml_str, GeneratorResult, PaletteGenerator};
+
+// PALETTE FILES
+// =============
+// The format is detected from the contents where possible, and the extension otherwise:
+//  - GIMP (.gpl)       - "GIMP Palette", followed by `R G B name` lines.
+//  - JASC (.pal)       - "JASC-PAL", a version, a count, then `R G B` lines.
+//  - RIFF (.pal)       - the binary Microsoft palette, which shares JASC's extension.
+//  - Adobe (.ase)      - binary swatch exchange files. RGB, CMYK, LAB and Gray swatches are read.
+//  - Paint.NET (.txt)  - `AARRGGBB` lines, with `;` comments.
+//  - Hex (.hex)        - `RRGGBB` lines, as exported by Lospec.
+//  - Images (.png)     - swatch strips. Every distinct opaque colour is taken, in reading order.
+//
+// Palette names, colour names and comments are kept in the [PaletteFile].
+
+/// Swatch images with more colours than this are assumed to not be swatches at all.
+const MAX_SWATCH_COLOURS: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaletteFormat {
+    Gimp,
+    Jasc,
+    Riff,
+    Ase,
+    PaintNet,
+    Hex,
+    Image,
+}
+
+#[derive(Debug, Default)]
+pub struct PaletteFile {
+    pub name: Option<String>,
+    pub comments: Vec<String>,
+    pub colours: Vec<(Srgb, Option<String>)>,
+}
+
+impl PaletteFile {
+    pub fn srgb(&self) -> Vec<Srgb> {
+        self.colours.iter().map(|(colour, _)| *colour).collect()
+    }
+
+    fn push(&mut self, colour: Srgb, name: Option<String>) {
+        self.colours
+            .push((colour, name.filter(|name| !name.is_empty())));
+    }
+}
+
+impl PaletteFormat {
+    pub fn detect(path: &Path, bytes: &[u8]) -> Option<Self> {
+        let text = String::from_utf8_lossy(&bytes[..bytes.len().min(64)]);
+
+        if bytes.starts_with(b"ASEF") {
+            Some(PaletteFormat::Ase)
+        } else if bytes.starts_with(b"RIFF") && bytes.get(8..12) == Some(b"PAL ") {
+            Some(PaletteFormat::Riff)
+        } else if text.trim_start().starts_with("GIMP Palette") {
+            Some(PaletteFormat::Gimp)
+        } else if text.trim_start().starts_with("JASC-PAL") {
+            Some(PaletteFormat::Jasc)
+        } else if image::guess_format(bytes).is_ok() {
+            Some(PaletteFormat::Image)
+        } else {
+            let ext = path.extension()?.to_str()?.to_lowercase();
+            match ext.as_str() {
+                "gpl" => Some(PaletteFormat::Gimp),
+                "pal" => Some(PaletteFormat::Jasc),
+                "ase" => Some(PaletteFormat::Ase),
+                "txt" => Some(PaletteFormat::PaintNet),
+                "hex" => Some(PaletteFormat::Hex),
+                _ => None,
+            }
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PaletteFormat::Gimp => "GIMP",
+            PaletteFormat::Jasc => "JASC",
+            PaletteFormat::Riff => "RIFF",
+            PaletteFormat::Ase => "ASE",
+            PaletteFormat::PaintNet => "Paint.NET",
+            PaletteFormat::Hex => "hex",
+            PaletteFormat::Image => "swatch image",
+        }
+    }
+
+    pub fn parse(&self, bytes: &[u8]) -> GeneratorResult<PaletteFile> {
+        match self {
+            PaletteFormat::Gimp => parse_gimp(&String::from_utf8_lossy(bytes)),
+            PaletteFormat::Jasc => parse_jasc(&String::from_utf8_lossy(bytes)),
+            PaletteFormat::Riff => parse_riff(bytes),
+            PaletteFormat::Ase => parse_ase(bytes),
+            PaletteFormat::PaintNet => parse_hex_lines(&String::from_utf8_lossy(bytes), ';'),
+            PaletteFormat::Hex => parse_hex_lines(&String::from_utf8_lossy(bytes), '#'),
+            PaletteFormat::Image => parse_swatch_image(bytes),
+        }
+    }
+}
+
+/// Reads the palette file at `path`, detecting its format.
+pub fn read_palette_file(path: impl AsRef<Path>) -> GeneratorResult<(PaletteFormat, PaletteFile)> {
+    let path = path.as_ref();
+    let bytes = std::fs::read(path)
+        .map_err(|err| format!("could not read palette file [{}]: {err}", path.display()))?;
+
+    let format = PaletteFormat::detect(path, &bytes).ok_or_else(|| {
+        format!(
+            "could not detect the format of palette file [{}].",
+            path.display()
+        )
+    })?;
+
+    let palette = format.parse(&bytes).map_err(|err| {
+        format!(
+            "could not read palette file [{}] as {}: {err}",
+            path.display(),
+            format.name()
+        )
+    })?;
+
+    if palette.colours.is_empty() {
+        return Err(format!("palette file [{}] has no colours.", path.display()).into());
+    }
+
+    Ok((format, palette))
+}
+
+/// A palette read from a file - the same one every time.
+#[derive(Debug, Clone)]
+pub struct FilePalette {
+    pub path: PathBuf,
+}
+
+impl FilePalette {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        FilePalette { path: path.into() }
+    }
+
+    pub fn from_yaml(value: &Value) -> GeneratorResult<Self> {
+        let path = yaml_str(value, "path")?
+            .ok_or("if [palette.type] is \"file\", [palette.path] must be present.")?;
+        Ok(FilePalette::new(path))
+    }
+
+    /// Reads the file, keeping its format, names and comments.
+    pub fn read(&self) -> GeneratorResult<(PaletteFormat, PaletteFile)> {
+        read_palette_file(&self.path)
+    }
+}
+
+impl PaletteGenerator for FilePalette {
+    fn generate(&self, _rng: &mut dyn RngCore) -> GeneratorResult<Vec<Srgb>> {
+        Ok(self.read()?.1.srgb())
+    }
+}
+
+fn parse_u8_components(line: &str, n: usize) -> GeneratorResult<(Srgb, String)> {
+    let mut parts = line.split_whitespace();
+    let mut components = [0u8; 3];
+
+    for component in components.iter_mut() {
+        let part = parts
+            .next()
+            .ok_or_else(|| format!("line {n} [{line}] must have 3 colour components."))?;
+        *component = part
+            .parse()
+            .map_err(|_| format!("line {n} [{line}] has an invalid component [{part}]."))?;
+    }
+
+    let name = parts.collect::<Vec<_>>().join(" ");
+
+    Ok((
+        Srgb::new(components[0], components[1], components[2]).into_format(),
+        name,
+    ))
+}
+
+fn parse_gimp(text: &str) -> GeneratorResult<PaletteFile> {
+    let mut palette = PaletteFile::default();
+
+    // the first line is the "GIMP Palette" header.
+    for (n, line) in text.lines().enumerate().skip(1) {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        } else if let Some(comment) = line.strip_prefix('#') {
+            palette.comments.push(comment.trim().to_string());
+        } else if let Some(name) = line.strip_prefix("Name:") {
+            palette.name = Some(name.trim().to_string());
+        } else if line.starts_with("Columns:") {
+            continue;
+        } else {
+            let (colour, name) = parse_u8_components(line, n + 1)?;
+            palette.push(colour, Some(name));
+        }
+    }
+
+    Ok(palette)
+}
+
+fn parse_jasc(text: &str) -> GeneratorResult<PaletteFile> {
+    let mut palette = PaletteFile::default();
+
+    // "JASC-PAL", the version, then the number of colours.
+    for (n, line) in text.lines().enumerate().skip(3) {
+        let line = line.trim();
+        if !line.is_empty() {
+            let (colour, _) = parse_u8_components(line, n + 1)?;
+            palette.push(colour, None);
+        }
+    }
+
+    Ok(palette)
+}
+
+fn parse_riff(bytes: &[u8]) -> GeneratorResult<PaletteFile> {
+    let mut palette = PaletteFile::default();
+    let mut offset = 12;
+
+    while offset + 8 <= bytes.len() {
+        let chunk = &bytes[offset..offset + 4];
+        let size = u32::from_le_bytes(bytes[offset + 4..offset + 8].try_into()?) as usize;
+        let data = bytes
+            .get(offset + 8..offset + 8 + size)
+            .ok_or("a chunk is cut short.")?;
+
+        if chunk == b"data" {
+            // the version, and number of colours, come first.
+            for entry in data.get(4..).unwrap_or_default().chunks_exact(4) {
+                palette.push(Srgb::new(entry[0], entry[1], entry[2]).into_format(), None);
+            }
+        }
+
+        // chunks are padded to an even size.
+        offset += 8 + size + size % 2;
+    }
+
+    Ok(palette)
+}
+
+fn parse_ase(bytes: &[u8]) -> GeneratorResult<PaletteFile> {
+    let mut reader = BeReader { bytes, offset: 4 };
+    let mut palette = PaletteFile::default();
+
+    let _version = (reader.u16()?, reader.u16()?);
+    let blocks = reader.u32()?;
+
+    for _ in 0..blocks {
+        let kind = reader.u16()?;
+        let length = reader.u32()? as usize;
+        let end = reader.offset + length;
+
+        match kind {
+            // a colour entry.
+            0x0001 => {
+                let name = reader.utf16()?;
+                let model = reader.take(4)?;
+                let colour: Srgb = match model {
+                    b"RGB " => Srgb::new(reader.f32()?, reader.f32()?, reader.f32()?),
+                    b"CMYK" => {
+                        let (c, m, y, k) =
+                            (reader.f32()?, reader.f32()?, reader.f32()?, reader.f32()?);
+                        Srgb::new(
+                            (1.0 - c) * (1.0 - k),
+                            (1.0 - m) * (1.0 - k),
+                            (1.0 - y) * (1.0 - k),
+                        )
+                    }
+                    b"LAB " => {
+                        Lab::new(reader.f32()? * 100.0, reader.f32()?, reader.f32()?).into_color()
+                    }
+                    b"Gray" => {
+                        let grey = reader.f32()?;
+                        Srgb::new(grey, grey, grey)
+                    }
+                    model => {
+                        return Err(format!(
+                            "swatch [{name}] has an unknown colour model [{}].",
+                            String::from_utf8_lossy(model)
+                        )
+                        .into())
+                    }
+                };
+                palette.push(colour.clamp(), Some(name));
+            }
+            // the start of a group - its name is kept as a comment.
+            0xc001 => {
+                if length > 0 {
+                    palette.comments.push(format!("group: {}", reader.utf16()?));
+                }
+            }
+            _ => {}
+        }
+
+        reader.offset = end;
+    }
+
+    Ok(palette)
+}
+
+fn parse_hex_lines(text: &str, comment: char) -> GeneratorResult<PaletteFile> {
+    let mut palette = PaletteFile::default();
+
+    for (n, line) in text.lines().enumerate() {
+        let line = line.trim();
+
+        // Lospec's .hex files don't have comments, but do allow a leading [#] on colours.
+        let is_hex_colour = line
+            .strip_prefix('#')
+            .is_some_and(|hex| hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()));
+
+        if line.is_empty() {
+            continue;
+        } else if line.starts_with(comment) && !is_hex_colour {
+            palette.comments.push(line[1..].trim().to_string());
+            continue;
+        }
+
+        let hex = line.trim_start_matches('#');
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!("line {} [{line}] is not a valid hex colour.", n + 1).into());
+        }
+
+        // Paint.NET stores an alpha component first, which is ignored.
+        let hex = match hex.len() {
+            8 => &hex[2..],
+            6 => hex,
+            _ => return Err(format!("line {} [{line}] is not a valid hex colour.", n + 1).into()),
+        };
+
+        let colour = u32::from_str_radix(hex, 16)
+            .map_err(|_| format!("line {} [{line}] is not a valid hex colour.", n + 1))?;
+
+        palette.push(
+            Srgb::new((colour >> 16) as u8, (colour >> 8) as u8, colour as u8).into_format(),
+            None,
+        );
+    }
+
+    Ok(palette)
+}
+
+fn parse_swatch_image(bytes: &[u8]) -> GeneratorResult<PaletteFile> {
+    let image = image::load_from_memory(bytes)?;
+    let mut palette = PaletteFile::default();
+    let mut seen = vec![];
+
+    for (_, _, pixel) in image.pixels() {
+        let [r, g, b, a] = pixel.0;
+        if a == 0 || seen.contains(&[r, g, b]) {
+            continue;
+        }
+
+        seen.push([r, g, b]);
+        if seen.len() > MAX_SWATCH_COLOURS {
+            return Err(format!(
+                "more than {MAX_SWATCH_COLOURS} distinct colours were found - this doesn't look like a swatch image."
+            )
+            .into());
+        }
+
+        palette.push(Srgb::new(r, g, b).into_format(), None);
+    }
+
+    Ok(palette)
+}
+
+/// Reads big-endian values, as used by .ase files.
+struct BeReader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> BeReader<'a> {
+    fn take(&mut self, n: usize) -> GeneratorResult<&'a [u8]> {
+        let bytes = self
+            .bytes
+            .get(self.offset..self.offset + n)
+            .ok_or("the file ends unexpectedly.")?;
+        self.offset += n;
+        Ok(bytes)
+    }
+
+    fn u16(&mut self) -> GeneratorResult<u16> {
+        Ok(u16::from_be_bytes(self.take(2)?.try_into()?))
+    }
+
+    fn u32(&mut self) -> GeneratorResult<u32> {
+        Ok(u32::from_be_bytes(self.take(4)?.try_into()?))
+    }
+
+    fn f32(&mut self) -> GeneratorResult<f32> {
+        Ok(f32::from_be_bytes(self.take(4)?.try_into()?))
+    }
+
+    /// A length-prefixed, null-terminated UTF-16 string.
+    fn utf16(&mut self) -> GeneratorResult<String> {
+        let length = self.u16()? as usize;
+        let units = (0..length)
+            .map(|_| self.u16())
+            .collect::<GeneratorResult<Vec<_>>>()?;
+        Ok(String::from_utf16_lossy(&units)
+            .trim_end_matches('\0')
+            .to_string())
+    }
+}
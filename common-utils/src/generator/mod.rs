@@ -0,0 +1,132 @@
+use std::error::Error;
+use std::path::Path;
+
+use palette::rgb::Rgb;
+use rand::RngCore;
+use serde_yaml::Value;
+
+use crate::effectlog::{ExecLog, LogEntry};
+use crate::palette::LoggedPalette;
+
+pub mod extract;
+pub mod file;
+pub mod named;
+pub mod random_v1;
+pub mod random_v2;
+pub mod reader;
+pub mod space;
+
+pub use extract::Extract;
+pub use file::FilePalette;
+pub use named::Named;
+pub use random_v1::RandomV1;
+pub use random_v2::RandomV2;
+
+// PALETTE GENERATORS
+// ==================
+// Every way of coming up with a palette, shared between the apps:
+//  - RandomV1      - a handful of random colours, sometimes with shades, usually with black & white.
+//  - RandomV2      - hues, lightness and chroma each picked by a configurable strategy.
+//  - Named         - one of the curated palettes from [palette::palettes].
+//  - FilePalette   - a palette file (.gpl, .pal, .ase, .txt, .hex or a swatch .png).
+//  - Extract       - the dominant colours of an image.
+//
+// Each is a plain struct that can be built directly, or read from YAML via [from_yaml] - in
+//  the same shape as an imgtoy palette:
+//
+//      type: named
+//      name: [nightlife, corru]
+//
+// Outside of imgtoy, every value has to be given exactly - ranges and choices like
+//  `{ min: 0, max: 10 }` are only understood by imgtoy's own parsers. For random_v2, imgtoy
+//  reads the same YAML through its own [reader::ConfigReader].
+
+pub type GeneratorResult<T> = Result<T, Box<dyn Error>>;
+
+pub trait PaletteGenerator: Send + Sync {
+    fn generate(&self, rng: &mut dyn RngCore) -> GeneratorResult<Vec<Rgb>>;
+
+    /// Same as `generate`, along with a log of how the palette was built.
+    fn generate_logged(&self, rng: &mut dyn RngCore) -> GeneratorResult<LoggedPalette> {
+        let palette = self.generate(rng)?;
+
+        let mut log = ExecLog::new();
+        for colour in &palette {
+            log.add_entry(LogEntry::colour(*colour));
+        }
+
+        Ok((palette, log))
+    }
+}
+
+/// Reads a generator from a palette config, such as `{ type: named, name: nightlife }`.
+pub fn from_yaml(value: &Value) -> GeneratorResult<Box<dyn PaletteGenerator>> {
+    let palette_type = yaml_str(value, "type")?.ok_or("[palette.type] must be specified.")?;
+
+    Ok(match palette_type {
+        "random_v1" => Box::new(RandomV1),
+        "random_v2" => {
+            let config = value
+                .get("config")
+                .ok_or("if [palette.type] is \"random_v2\", [palette.config] must be present.")?;
+            Box::new(RandomV2::from_yaml(config)?)
+        }
+        "named" => Box::new(Named::from_yaml(value)?),
+        "file" => Box::new(FilePalette::from_yaml(value)?),
+        "extract" => Box::new(Extract::from_yaml(value)?),
+        _ => return Err(format!("{palette_type} is not a valid palette type.").into()),
+    })
+}
+
+/// Reads a generator from a YAML file holding a single palette config.
+pub fn load_generator(path: impl AsRef<Path>) -> GeneratorResult<Box<dyn PaletteGenerator>> {
+    let path = path.as_ref();
+    let text = std::fs::read_to_string(path)
+        .map_err(|err| format!("could not read palette config [{}]: {err}", path.display()))?;
+    let value = serde_yaml::from_str::<Value>(&text)
+        .map_err(|err| format!("could not parse palette config [{}]: {err}", path.display()))?;
+
+    from_yaml(&value)
+}
+
+pub(crate) fn yaml_f64(value: &Value, name: &str) -> GeneratorResult<Option<f64>> {
+    value
+        .get(name)
+        .map(|number| {
+            number
+                .as_f64()
+                .ok_or_else(|| format!("[{name}] must be a number.").into())
+        })
+        .transpose()
+}
+
+pub(crate) fn yaml_u64(value: &Value, name: &str) -> GeneratorResult<Option<u64>> {
+    value
+        .get(name)
+        .map(|number| {
+            number
+                .as_u64()
+                .ok_or_else(|| format!("[{name}] must be a positive integer.").into())
+        })
+        .transpose()
+}
+
+pub(crate) fn yaml_str<'a>(value: &'a Value, name: &str) -> GeneratorResult<Option<&'a str>> {
+    value
+        .get(name)
+        .map(|string| {
+            string
+                .as_str()
+                .ok_or_else(|| format!("[{name}] must be a string.").into())
+        })
+        .transpose()
+}
+
+pub(crate) fn yaml_bool(value: &Value, name: &str) -> GeneratorResult<bool> {
+    match value.get(name) {
+        None => Ok(false),
+        Some(flag) => flag
+            .as_bool()
+            .ok_or_else(|| format!("[{name}] must be a boolean.").into()),
+    }
+}
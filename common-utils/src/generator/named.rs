@@ -0,0 +1,70 @@
+use palette::rgb::Rgb;
+use rand::{seq::SliceRandom, RngCore};
+use serde_yaml::Value;
+
+use crate::palette::palettes;
+
+use super::{GeneratorResult, PaletteGenerator};
+
+/// One of the curated palettes from [palettes] - picked at random if there's more than one name.
+#[derive(Debug, Clone)]
+pub struct Named {
+    pub names: Vec<String>,
+}
+
+impl Named {
+    pub fn new(name: impl Into<String>) -> Self {
+        Named {
+            names: vec![name.into()],
+        }
+    }
+
+    /// The names of every curated palette.
+    pub fn available() -> Vec<String> {
+        palettes()
+            .into_iter()
+            .map(|(name, _)| name.to_string())
+            .collect()
+    }
+
+    pub fn from_yaml(value: &Value) -> GeneratorResult<Self> {
+        let names = match value
+            .get("name")
+            .ok_or("if [palette.type] is \"named\", [palette.name] must be present.")?
+        {
+            Value::String(name) => vec![name.clone()],
+            Value::Sequence(names) => names
+                .iter()
+                .map(|name| name.as_str().map(String::from))
+                .collect::<Option<Vec<_>>>()
+                .ok_or("[palette.name] must be a name, or a list of names.")?,
+            _ => return Err("[palette.name] must be a name, or a list of names.".into()),
+        };
+
+        Ok(Named { names })
+    }
+}
+
+impl PaletteGenerator for Named {
+    fn generate(&self, rng: &mut dyn RngCore) -> GeneratorResult<Vec<Rgb>> {
+        // a single name is used as-is, so it doesn't draw from the rng.
+        let name = match self.names.as_slice() {
+            [name] => name,
+            names => names
+                .choose(rng)
+                .ok_or("[palette.name] must have at least one name.")?,
+        };
+
+        palettes()
+            .into_iter()
+            .find(|(palette, _)| palette == name)
+            .map(|(_, colours)| colours)
+            .ok_or_else(|| {
+                format!(
+                    "[palette.name] {name} is not a known palette. available are: {}",
+                    Named::available().join(", ")
+                )
+                .into()
+            })
+    }
+}
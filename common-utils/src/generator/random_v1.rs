@@ -0,0 +1,92 @@
+use image_effects::prelude::IntoGradientLch;
+use palette::{named, rgb::Rgb, IntoColor, Lch};
+use rand::{Rng, RngCore};
+
+use crate::effectlog::{ExecLog, LogEntry};
+use crate::palette::LoggedPalette;
+
+use super::{space::GenerationSpace, GeneratorResult, PaletteGenerator};
+
+/// The original random palette - a light, a mid and a dark colour, up to 9 more of any
+/// lightness, each with a chance of being expanded into shades. Usually ends with black & white.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RandomV1;
+
+impl RandomV1 {
+    /// Same as `generate_logged` - which can't fail for v1, so isn't wrapped in a result.
+    pub fn palette(&self, rng: &mut (impl Rng + ?Sized)) -> LoggedPalette {
+        // unlike [gen_with_lightness], chroma isn't capped - v1 palettes are kept as they were.
+        fn gen_unclamped(rng: &mut (impl Rng + ?Sized), min: f32, max: f32) -> Lch {
+            Lch::new(
+                rng.gen_range(min..=max),
+                rng.gen_range(0.0..128.0),
+                rng.gen_range(0.0..360.0),
+            )
+        }
+
+        let mut palette = vec![
+            gen_unclamped(rng, 80.0, 100.0),
+            gen_unclamped(rng, 20.0, 80.0),
+            gen_unclamped(rng, 0.0, 20.0),
+        ];
+
+        for _ in 0..rng.gen_range(0..10) {
+            palette.push(gen_unclamped(rng, 0.0, 100.0));
+        }
+
+        let mut log = ExecLog::new();
+
+        let mut palette = palette
+            .into_iter()
+            .map(|col| {
+                let col: Rgb = col.into_color();
+                col
+            })
+            .map(|col| {
+                if rng.gen_bool(0.10) {
+                    let amnt = rng.gen_range(2..=10);
+                    log.add_entry(LogEntry::gradient(col, amnt));
+                    col.build_gradient_lch(amnt)
+                } else {
+                    log.add_entry(LogEntry::colour(col));
+                    vec![col]
+                }
+            })
+            .collect::<Vec<_>>()
+            .concat();
+
+        if rng.gen_bool(0.75) {
+            palette.push(named::BLACK.into_format());
+            log.add_entry(LogEntry::colour(named::BLACK.into_format()));
+            palette.push(named::WHITE.into_format());
+            log.add_entry(LogEntry::colour(named::WHITE.into_format()));
+        }
+
+        (palette, log)
+    }
+}
+
+impl PaletteGenerator for RandomV1 {
+    fn generate(&self, rng: &mut dyn RngCore) -> GeneratorResult<Vec<Rgb>> {
+        Ok(self.palette(rng).0)
+    }
+
+    fn generate_logged(&self, rng: &mut dyn RngCore) -> GeneratorResult<LoggedPalette> {
+        Ok(self.palette(rng))
+    }
+}
+
+pub fn gen_with_random_lightness(rng: &mut (impl Rng + ?Sized), min: f32, max: f32) -> Lch {
+    let lum = rng.gen_range(min..=max);
+    gen_with_lightness(rng, lum)
+}
+
+/// Chroma is kept within sRGB, so the colour keeps its lightness once converted.
+pub fn gen_with_lightness(rng: &mut (impl Rng + ?Sized), lum: f32) -> Lch {
+    let (chroma, hue): (f32, f32) = (rng.gen_range(0.0..128.0), rng.gen_range(0.0..360.0));
+    Lch::new(
+        lum,
+        chroma.min(GenerationSpace::Lch.max_chroma(lum, hue)),
+        hue,
+    )
+}
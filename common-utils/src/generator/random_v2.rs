@@ -0,0 +1,862 @@
+use std::ops::Range;
+
+use palette::{rgb::Rgb, Lch};
+use rand::{
+    distributions::{Distribution, WeightedIndex},
+    seq::SliceRandom,
+    Rng, RngCore,
+};
+use serde_yaml::Value;
+
+use super::{
+    random_v1::gen_with_random_lightness,
+    reader::{ConfigReader, Exact},
+    space::GenerationSpace,
+    yaml_bool, yaml_str, GeneratorResult, PaletteGenerator,
+};
+
+// RANDOM V2
+// =========
+// Hues are picked first - a seed hue, plus whatever the hue strategies add around it. Each
+//  hue then gets a set of lightnesses from the lum strategy, and finally every colour gets a
+//  chroma from the chroma strategy.
+//
+// The strategies (and their YAML) are described in full below, under LUM STRATEGY,
+//  HUE STRATEGY and CHROMA STRATEGY. Values are read through a [ConfigReader], so imgtoy
+//  can give ranges and choices for them as well.
+
+pub enum LumStrategy {
+    Exact(Vec<f64>),
+    Random { unified: bool },
+    Distributed,
+    DistributedArea { overlap: Option<f64> },
+    DistributedNudge { nudge_size: f64, unified: bool },
+}
+
+pub enum HueDistribution {
+    Linear,
+    Random,
+}
+
+impl HueDistribution {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "linear" => Some(HueDistribution::Linear),
+            "random" => Some(HueDistribution::Random),
+            _ => None,
+        }
+    }
+}
+
+pub enum HueStrategy {
+    Neighbour {
+        size: f64,
+        n: u64,
+        dist: HueDistribution,
+    },
+    Contrast {
+        size: f64,
+        n: u64,
+        dist: HueDistribution,
+    },
+    Penpal {
+        size: f64,
+        n: u64,
+        dist: HueDistribution,
+        distance: f64,
+    },
+    Cycle {
+        n: u64,
+    },
+    Harmony {
+        offsets: Vec<f64>,
+        spread: Option<(f64, u64, HueDistribution)>,
+    },
+}
+
+pub enum ChromaStrategy {
+    Random(Range<f64>),
+    Fixed(f64),
+    Distributed(Range<f64>),
+    Curve {
+        min: f64,
+        peak: f64,
+        peak_lum: f64,
+        power: f64,
+    },
+    Unified(Range<f64>),
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MiscFlags {
+    pub lum_safeguard: bool,
+    pub extremes: bool,
+    pub single_lum: bool,
+    pub grayscale: bool,
+}
+
+pub struct RandomV2 {
+    pub min_lum: f64,
+    pub max_lum: f64,
+    pub space: GenerationSpace,
+    /// Whether out-of-gamut colours have their chroma reduced, rather than being clipped.
+    pub gamut_map: bool,
+    pub lum_strategy: LumStrategy,
+    pub lum_count: u64,
+    pub hue_strategies: Vec<HueStrategy>,
+    /// Random if not set.
+    pub seed_hue: Option<f64>,
+    pub chroma_strategy: ChromaStrategy,
+    pub clamp_chroma: bool,
+    pub flags: MiscFlags,
+}
+
+/// The offsets from the seed hue for each named harmony, as listed in HUE STRATEGY.
+pub fn harmony_offsets(name: &str, angle: f64) -> Option<Vec<f64>> {
+    Some(match name {
+        "analogous" => vec![-angle, angle],
+        "complementary" => vec![180.0],
+        "split-complementary" => vec![180.0 - angle, 180.0 + angle],
+        "triadic" => vec![120.0, 240.0],
+        "tetradic" => vec![60.0, 180.0, 240.0],
+        "square" => vec![90.0, 180.0, 270.0],
+        _ => return None,
+    })
+}
+
+impl PaletteGenerator for RandomV2 {
+    fn generate(&self, rng: &mut dyn RngCore) -> GeneratorResult<Vec<Rgb>> {
+        let RandomV2 {
+            min_lum,
+            max_lum,
+            lum_count: lum_amnt,
+            flags,
+            ..
+        } = *self;
+
+        let mut palette: Vec<Lch> = Vec::new();
+
+        let seed_hue = self.seed_hue.unwrap_or_else(|| rng.gen_range(0.0..360.0));
+        let mut hues = vec![seed_hue];
+
+        // fn for common hue calculation
+        let mut generate_hue_neighbourhood =
+            |hue: f64, size: f64, n: u64, dist: &HueDistribution| {
+                let mut neighbourhood = Vec::new();
+
+                let lower_end = hue - size;
+                let upper_end = hue + size;
+
+                for i in 0..n {
+                    match dist {
+                        HueDistribution::Linear => {
+                            let fraction = (i as f64) / ((n - 1) as f64);
+                            neighbourhood.push(lower_end + (size * 2.0 * fraction));
+                        }
+                        HueDistribution::Random => {
+                            neighbourhood.push(rng.gen_range(lower_end..upper_end));
+                        }
+                    }
+                }
+
+                neighbourhood
+            };
+
+        // hue strategy application
+        for strategy in self.hue_strategies.iter() {
+            match strategy {
+                HueStrategy::Neighbour { size, n, dist } => {
+                    hues = [hues, generate_hue_neighbourhood(seed_hue, *size, *n, dist)].concat()
+                }
+                HueStrategy::Contrast { size, n, dist } => {
+                    hues = [
+                        hues,
+                        generate_hue_neighbourhood(seed_hue + 180.0, *size, *n, dist),
+                    ]
+                    .concat()
+                }
+                HueStrategy::Penpal {
+                    size,
+                    n,
+                    dist,
+                    distance,
+                } => {
+                    hues = [
+                        hues,
+                        generate_hue_neighbourhood(seed_hue + distance, *size, *n, dist),
+                    ]
+                    .concat()
+                }
+                HueStrategy::Cycle { n } => {
+                    for i in 1..=*n {
+                        hues.push(seed_hue + i as f64 * (360.0 / (*n as f64 + 1.0)));
+                    }
+                }
+                HueStrategy::Harmony { offsets, spread } => {
+                    for offset in offsets {
+                        match spread {
+                            Some((size, n, dist)) => {
+                                hues = [
+                                    hues,
+                                    generate_hue_neighbourhood(seed_hue + offset, *size, *n, dist),
+                                ]
+                                .concat()
+                            }
+                            None => hues.push(seed_hue + offset),
+                        }
+                    }
+                }
+            }
+        }
+
+        // unified strategies share their randomness across every hue.
+        let shared_lums = match &self.lum_strategy {
+            LumStrategy::Random { unified: true } => (0..lum_amnt)
+                .map(|_| rng.gen_range(0.0..100.0))
+                .collect::<Vec<f64>>(),
+            _ => vec![],
+        };
+        let shared_nudges = match &self.lum_strategy {
+            LumStrategy::DistributedNudge {
+                nudge_size,
+                unified: true,
+            } => (0..lum_amnt)
+                .map(|_| rng.gen_range((-nudge_size)..*nudge_size))
+                .collect::<Vec<f64>>(),
+            _ => vec![],
+        };
+
+        // lum strategy application
+        hues.into_iter().for_each(|hue| {
+            let hue = hue as f32;
+
+            match &self.lum_strategy {
+                LumStrategy::Exact(lums) => {
+                    for mut l in lums.iter() {
+                        if flags.single_lum {
+                            l = lums.choose(rng).unwrap()
+                        };
+                        palette.push(Lch::new(*l as f32, rng.gen_range(0.0..128.0), hue));
+                        if flags.single_lum {
+                            break;
+                        };
+                    }
+                }
+                LumStrategy::Random { unified } => {
+                    for i in 0..lum_amnt {
+                        let l = if *unified {
+                            shared_lums[i as usize] as f32
+                        } else {
+                            rng.gen_range(0.0..100.0)
+                        };
+                        palette.push(Lch::new(l, rng.gen_range(0.0..128.0), hue));
+                        if flags.single_lum {
+                            break;
+                        };
+                    }
+                }
+                LumStrategy::Distributed => {
+                    for mut i in 0..lum_amnt {
+                        if flags.single_lum {
+                            i = rng.gen_range(0..lum_amnt)
+                        };
+                        let span_size = max_lum - min_lum;
+                        let l = min_lum + (i as f64 / (lum_amnt as f64 - 1.0)) * span_size;
+                        palette.push(Lch::new(l as f32, rng.gen_range(0.0..128.0), hue));
+                        if flags.single_lum {
+                            break;
+                        };
+                    }
+                }
+                LumStrategy::DistributedArea { overlap } => {
+                    for mut i in 0..lum_amnt {
+                        if flags.single_lum {
+                            i = rng.gen_range(0..lum_amnt)
+                        };
+                        let span_size = max_lum - min_lum;
+                        let step_size = span_size / lum_amnt as f64;
+
+                        let mut area_start = min_lum + (i as f64 * step_size);
+                        let mut area_end = area_start + step_size;
+
+                        if let Some(overlap) = overlap {
+                            area_start = (area_start - overlap).max(min_lum);
+                            area_end = (area_end + overlap).min(max_lum);
+                        }
+
+                        let l = rng.gen_range(area_start..area_end) as f32;
+                        palette.push(Lch::new(l, rng.gen_range(0.0..128.0), hue));
+                        if flags.single_lum {
+                            break;
+                        };
+                    }
+                }
+                LumStrategy::DistributedNudge {
+                    nudge_size,
+                    unified,
+                } => {
+                    for mut i in 0..lum_amnt {
+                        if flags.single_lum {
+                            i = rng.gen_range(0..lum_amnt)
+                        };
+                        let span_size = max_lum - min_lum;
+                        let mut l = min_lum + (i as f64 / (lum_amnt as f64 - 1.0)) * span_size;
+
+                        let nudge = if *unified {
+                            shared_nudges[i as usize]
+                        } else {
+                            rng.gen_range((-nudge_size)..*nudge_size)
+                        };
+
+                        l = (l + nudge).clamp(0.0, 100.0);
+
+                        palette.push(Lch::new(l as f32, rng.gen_range(0.0..128.0), hue));
+                        if flags.single_lum {
+                            break;
+                        };
+                    }
+                }
+            }
+        });
+
+        // chroma strategy application
+        let (darkest, lightest) = palette.iter().fold((f32::MAX, f32::MIN), |(lo, hi), col| {
+            (lo.min(col.l), hi.max(col.l))
+        });
+        let mut hue_chromas: Vec<(f32, f32)> = vec![];
+
+        palette.iter_mut().for_each(|col| {
+            if flags.grayscale {
+                col.chroma = 0.0;
+                return;
+            }
+
+            col.chroma = match &self.chroma_strategy {
+                ChromaStrategy::Random(range) => rng.gen_range(range.clone()) as f32,
+                ChromaStrategy::Fixed(chroma) => *chroma as f32,
+                ChromaStrategy::Distributed(range) => {
+                    let t = if lightest > darkest {
+                        (col.l - darkest) / (lightest - darkest)
+                    } else {
+                        0.5
+                    };
+                    (range.start + t as f64 * (range.end - range.start)) as f32
+                }
+                ChromaStrategy::Curve {
+                    min,
+                    peak,
+                    peak_lum,
+                    power,
+                } => {
+                    let spread = peak_lum.max(100.0 - peak_lum).max(f64::EPSILON);
+                    let t = 1.0 - ((col.l as f64 - peak_lum).abs() / spread).clamp(0.0, 1.0);
+                    (min + (peak - min) * t.powf(*power)) as f32
+                }
+                ChromaStrategy::Unified(range) => {
+                    let hue = col.hue.into_positive_degrees();
+                    match hue_chromas.iter().find(|(h, _)| *h == hue) {
+                        Some((_, chroma)) => *chroma,
+                        None => {
+                            let chroma = rng.gen_range(range.clone()) as f32;
+                            hue_chromas.push((hue, chroma));
+                            chroma
+                        }
+                    }
+                }
+            };
+
+            if self.clamp_chroma {
+                col.chroma = col.chroma.min(
+                    self.space
+                        .max_chroma(col.l, col.hue.into_positive_degrees()),
+                );
+            }
+        });
+
+        // injection
+        if flags.lum_safeguard {
+            palette.push(gen_with_random_lightness(rng, 80.0, 100.0));
+            palette.push(gen_with_random_lightness(rng, 20.0, 80.0));
+            palette.push(gen_with_random_lightness(rng, 0.0, 20.0));
+        }
+
+        if flags.extremes {
+            palette.push(Lch::new(0.0, 0.0, 0.0));
+            palette.push(Lch::new(100.0, 128.0, 0.0));
+        }
+
+        Ok(palette
+            .into_iter()
+            .map(|colour| match self.gamut_map {
+                true => self.space.map_to_srgb(colour),
+                false => self.space.to_srgb(colour),
+            })
+            .collect())
+    }
+}
+
+impl RandomV2 {
+    /// Reads a random_v2 [config] - every value has to be given exactly.
+    ///
+    /// A [lum-strategy.strategy-pool] is picked from once, here.
+    pub fn from_yaml(config: &Value) -> GeneratorResult<Self> {
+        Self::read(config, &mut Exact::new(&mut rand::thread_rng()))
+    }
+
+    /// Reads a random_v2 [config], with `reader` deciding how each value is read.
+    pub fn read(config: &Value, reader: &mut impl ConfigReader) -> GeneratorResult<Self> {
+        let max_lum = reader.f64(config, "max-lum")?.unwrap_or(100.0);
+        let min_lum = reader.f64(config, "min-lum")?.unwrap_or(0.0);
+
+        let space = match reader.str(config, "space")?.as_deref() {
+            None => GenerationSpace::Lch,
+            Some(space) => GenerationSpace::from_name(space).ok_or_else(|| {
+                format!("{space} is not a valid [space] - expected lch or oklch.")
+            })?,
+        };
+        let gamut_map = match reader.str(config, "gamut")?.as_deref() {
+            None | Some("map") => true,
+            Some("clip") => false,
+            Some(gamut) => {
+                return Err(
+                    format!("{gamut} is not a valid [gamut] - expected map or clip.").into(),
+                )
+            }
+        };
+
+        // each category is closed even if reading it fails, before the error is passed on.
+        reader.begin("lum-strategy")?;
+        let lum_strategy = read_lum_strategy(config, reader);
+        reader.end()?;
+        let (lum_strategy, lum_count) = lum_strategy?;
+
+        reader.begin("hue-strategies")?;
+        let hue_strategies = read_hue_strategies(config, reader);
+        reader.end()?;
+        let hue_strategies = hue_strategies?;
+
+        reader.begin("chroma-strategy")?;
+        let chroma_strategy = read_chroma_strategy(config, reader);
+        reader.end()?;
+        let (chroma_strategy, clamp_chroma) = chroma_strategy?;
+
+        let flags = match config.get("misc_flags") {
+            None => MiscFlags::default(),
+            Some(flags) => {
+                let flags = flags
+                    .as_sequence()
+                    .and_then(|flags| flags.iter().map(Value::as_str).collect::<Option<Vec<_>>>())
+                    .ok_or("[misc_flags] must be a list of strings.")?;
+                MiscFlags {
+                    lum_safeguard: flags.contains(&"lum_safeguard"),
+                    extremes: flags.contains(&"extremes"),
+                    single_lum: flags.contains(&"single_lum"),
+                    grayscale: flags.contains(&"grayscale"),
+                }
+            }
+        };
+
+        let seed_hue = reader.seed_hue(config)?;
+
+        Ok(RandomV2 {
+            min_lum,
+            max_lum,
+            space,
+            gamut_map,
+            lum_strategy,
+            lum_count,
+            hue_strategies,
+            seed_hue,
+            chroma_strategy,
+            clamp_chroma,
+            flags,
+        })
+    }
+}
+
+// GENERATION SPACE
+// ================
+// random_v2 palettes are generated in LCH by default. With [space: oklch], they're generated
+//  in Oklch instead - which is more perceptually uniform, so evenly spaced lightness and hue
+//  *look* evenly spaced.
+//
+// Every strategy works on the same scales either way - lightness from 0 to 100, and chroma
+//  from 0 to ~128. For Oklch, these are scaled down to its own ranges (0-1, and 0-0.32).
+//
+// Colours that fall outside sRGB are mapped back in by reducing their chroma, keeping their
+//  lightness and hue - so lum strategies aren't thrown off by clipping. [gamut: clip] restores
+//  the old behaviour of clipping each channel instead.
+
+// LUM STRATEGY
+// ============
+// This will determine how the luminescence values will be generated.
+// Each HUE will be processed by the specified strategy into N variants,
+//  where N is specified by the user.
+// So, if there are H hues, and N lum-variants, there will be HxN total colours.
+//
+// Strategies here can have the following characteristics:
+//  - Uniform: One set of luminescence values is generated, and applied for all hues.
+//  - Chaotic: Every hue will have its own set of luminescence values.
+//  - Seeded: The strategy activates once the *first* value is generated.
+//
+// META:
+// range_size = how many variants to generate.
+// strategy = which strategy to depend on.
+//
+// -- experimental --
+// strategy-pool = a set of strategies to pick from. can specify odds.
+//      each entry is a lum-strategy of its own, with an optional [weight] (1 by default).
+//      one is picked per palette. entries without a [count] use the pool's own.
+//      for example:
+//          lum-strategy:
+//            count: 4
+//            strategy-pool:
+//              - { type: distributed, weight: 3 }
+//              - { type: pseudo-random }
+//
+// STRATEGIES:
+// "exact":
+//      variants will be generated for each specified LUM.
+//      as a result, this will DISABLE min_lum and max_lum, AND range_size.
+//      theoretically, since values are parsed to be *flexible*, you can
+//          manually replicate some of the below strategies.
+//      for example, "random" can be replicated by specifying four 0-100.0 ranges.
+//
+// "random":
+//      the variants will be generated with *random luminescence*.
+//      these levels will be random PER HUE.
+//      this will result in an effect where *luminescence* contributes to color difference,
+//
+// "pseudo-random":
+//      same as "random", except ALL HUES will share the same luminescence.
+//      this ensures that ONLY HUEs will affect color difference.
+//      equivalent to "random" with [unified] set to true.
+//
+// "distributed":
+//      the variants will be generated to cover the entire span of LUM.
+//      if N is 1, it will be the same as "random".
+//      if N is 2, the variants will both be at the extremes.
+//      for N > 2, additional variants should be split across the range.
+//          N=?, #X will be at (X-2 / N-1)
+//          N=3, #3 will be at 50% (3-2 / 3-1)
+//          N=4, #3 will be at 33% (3-2 / 4-1)
+//
+// "distributed/areas":
+//      same as distributed, except instead of static thresholds, it's within an area.
+//      in this case, N specifies the *number of areas*
+//      for example, N=3 results in 3 areas: 0.0~33.3, 33.3~66.6, 66.6~100.0.
+//      a luminescence will then be generated within each area.
+//      we can also choose to support an OVERLAP,
+//          for example, OVERLAP=10.0 and N=3 would result in *these* areas:
+//          0.0~43.3, 23.3~76.6, 56.6~100.0.
+//
+// "distributed/nudged":
+//      same as "distributed", except each colour will have its luminescence *nudged* randomly.
+//      this will have additional parameters:
+//          "nudge_range": the luminescence will be "nudged" by an amount within this range.
+//          "unified": if true, every colour will undergo the *same nudging*.
+//              note that the per-luminescence nudge will still be random, but
+//              the "nudge factors" will be shared per hue.
+fn read_lum_strategy(
+    config: &Value,
+    reader: &mut impl ConfigReader,
+) -> GeneratorResult<(LumStrategy, u64)> {
+    let lum_strategy = config
+        .get("lum-strategy")
+        .ok_or("[lum-strategy] is required to be specified.")?;
+
+    let count = reader.u64(lum_strategy, "count")?;
+
+    let (lum_strategy, count) = match lum_strategy.get("strategy-pool") {
+        Some(pool) => {
+            let pool = pool
+                .as_sequence()
+                .ok_or("[lum-strategy.strategy-pool] must be a list of lum-strategies.")?;
+
+            let weights = pool
+                .iter()
+                .map(|entry| match entry.get("weight") {
+                    None => Ok(1.0),
+                    Some(weight) => weight
+                        .as_f64()
+                        .ok_or("[strategy-pool.#.weight] must be a number."),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let picked = WeightedIndex::new(&weights)
+                .map_err(|err| format!("[lum-strategy.strategy-pool] has invalid weights: {err}"))?
+                .sample(reader.rng());
+
+            reader.state(
+                "picked",
+                &format!("#{picked:0>3} (weight {})", weights[picked]),
+            )?;
+
+            let entry = &pool[picked];
+            let count = reader.u64(entry, "count")?.or(count);
+
+            (entry, count)
+        }
+        None => (lum_strategy, count),
+    };
+    let count = count.ok_or("[lum-strategy.count] is required.")?;
+
+    Ok((read_lum_strategy_type(lum_strategy, reader)?, count))
+}
+
+fn read_lum_strategy_type(
+    lum_strategy: &Value,
+    reader: &mut impl ConfigReader,
+) -> GeneratorResult<LumStrategy> {
+    let strategy_type = reader
+        .str(lum_strategy, "type")?
+        .ok_or("[lum-strategy.type] must be a string.")?;
+    let unified = yaml_bool(lum_strategy, "unified")?;
+
+    Ok(match strategy_type.as_str() {
+        "exact" => LumStrategy::Exact(
+            reader
+                .f64_list(lum_strategy, "lums")?
+                .ok_or("[exact.lums] must be a list of numbers.")?,
+        ),
+        "random" => LumStrategy::Random { unified },
+        "pseudo-random" => LumStrategy::Random { unified: true },
+        "distributed" => LumStrategy::Distributed,
+        "distributed/area" => LumStrategy::DistributedArea {
+            overlap: reader.f64(lum_strategy, "overlap")?,
+        },
+        "distributed/nudge" => LumStrategy::DistributedNudge {
+            nudge_size: reader
+                .f64(lum_strategy, "nudge-size")?
+                .ok_or("if [lum-strategy] was [distributed/nudge], [nudge-size] is required.")?,
+            unified,
+        },
+        _ => return Err(format!("{strategy_type} is not a valid lum_strategy.").into()),
+    })
+}
+
+// HUE STRATEGY
+// ============
+// This determines how the hues will be generated.
+// Luminescence will be handled by the LUM STRATEGY, which will then
+//  create multiple instances of each hue generated here.
+//
+// Strategies here can have the following characteristics:
+//  - Chaotic: Each hue is generated by some random algorithm, without dependencies.
+//  - Seeded: Hues may be generated from a "seed".
+//
+// Strategies may be STACKED -- so this should be a list of strategies.
+// Seeded strategies will utilise the *same seed*.
+//
+// For example, for a seed of 120, you can generate a plethora of hues by stacking:
+// - neighbour (size=30, n=5, dist=linear)
+// - neighbour (size=90, n=5, dist=random)
+// - contrast (size=90, n=10)
+// - cycle (n=3)
+//
+// STRATEGIES:
+// "neighbour":
+//      hues will be chosen within a "neighbourhood".
+//      so for a "neighbourhood" size of 30, it will generate hues from -15 to +15
+//      of the "seed" hue. they can be generated within the range, or distributed.
+//      PARAMETERS: size, distribution
+//
+// "contrast":
+//      hues will be chosen from the "opposite neighbourhood".
+//      it's essentially the same as "neighbour", except when generating, the base
+//          is 180-S instead of S.
+//
+// "penpal":
+//      an extension on "contrast" -- it works the same, except now you control the distance.
+//      it is effectively equal at distance=180 - but allows to pick any distance.
+//      "contrast" remains due to being a common case, but may be removed for redundancy.
+//
+// "cycle":
+//      hues will be generated linearly over a 360-degree span.
+//      for example, N=1 will add a 180+S, N=2 will add 120+S and 240+S, etc...
+//      PARAMETERS: count (N)
+//
+// HARMONIES:
+//      named colour harmonies, which add hues at fixed offsets from the seed:
+//      "analogous":            S-A and S+A, where A is the [angle] (30 by default).
+//      "complementary":        S+180.
+//      "split-complementary":  S+180-A and S+180+A, where A is the [angle] (30 by default).
+//      "triadic":              S+120 and S+240.
+//      "tetradic":             S+60, S+180 and S+240 - a rectangle.
+//      "square":               S+90, S+180 and S+270.
+//      by default the exact hues are added - but if a [size] is specified, each hue
+//          gets a neighbourhood instead, same as "neighbour". [count] and [dist]
+//          then work the same way too.
+//
+// SEED HUE:
+//      the seed is random by default - but can be set via [seed-hue], next to
+//          [hue-strategies]. it's either a hue (in imgtoy, this can be a range or choice),
+//          or a colour - in which case the colour's own hue is used.
+//      e.g. `seed-hue: { rgb: FF6600 }` anchors the palette to a brand colour.
+fn read_hue_strategies(
+    config: &Value,
+    reader: &mut impl ConfigReader,
+) -> GeneratorResult<Vec<HueStrategy>> {
+    let strategies = config
+        .get("hue-strategies")
+        .and_then(Value::as_sequence)
+        .ok_or("[hue-strategies] must be a list of mappings.")?;
+
+    let mut hue_strategies = vec![];
+
+    for strategy in strategies {
+        let strategy_type =
+            yaml_str(strategy, "type")?.ok_or("[hue-strategies] entries must have a [.type].")?;
+
+        let required = |value: Option<f64>, name: &str| -> GeneratorResult<f64> {
+            value.ok_or_else(|| format!("[{strategy_type}] strategy requires a [.{name}].").into())
+        };
+        let required_count = |value: Option<u64>| -> GeneratorResult<u64> {
+            value.ok_or_else(|| format!("[{strategy_type}] strategy requires a [.count].").into())
+        };
+
+        for _ in 0..reader.u64(strategy, "iterations")?.unwrap_or(1) {
+            hue_strategies.push(match strategy_type {
+                "neighbour" | "contrast" | "penpal" => {
+                    let size = required(reader.f64(strategy, "size")?, "size")?;
+                    let n = required_count(reader.u64(strategy, "count")?)?;
+                    let dist = read_dist(strategy, strategy_type, reader, true)?;
+
+                    match strategy_type {
+                        "neighbour" => HueStrategy::Neighbour { size, n, dist },
+                        "contrast" => HueStrategy::Contrast { size, n, dist },
+                        _ => HueStrategy::Penpal {
+                            size,
+                            n,
+                            dist,
+                            distance: required(reader.f64(strategy, "distance")?, "distance")?,
+                        },
+                    }
+                }
+                "cycle" => HueStrategy::Cycle {
+                    n: required_count(reader.u64(strategy, "count")?)?,
+                },
+                harmony => {
+                    let angle = reader.f64(strategy, "angle")?.unwrap_or(30.0);
+                    let offsets = harmony_offsets(harmony, angle)
+                        .ok_or_else(|| format!("{harmony} is not a valid hue_strategy."))?;
+
+                    let spread = match reader.f64(strategy, "size")? {
+                        Some(size) => Some((
+                            size,
+                            reader.u64(strategy, "count")?.unwrap_or(1),
+                            read_dist(strategy, strategy_type, reader, false)?,
+                        )),
+                        None => None,
+                    };
+
+                    HueStrategy::Harmony { offsets, spread }
+                }
+            });
+        }
+    }
+
+    Ok(hue_strategies)
+}
+
+/// Reads a hue strategy's [dist] - "random" if it isn't `required`, and isn't given.
+fn read_dist(
+    strategy: &Value,
+    strategy_type: &str,
+    reader: &mut impl ConfigReader,
+    required: bool,
+) -> GeneratorResult<HueDistribution> {
+    let dist = match reader.str(strategy, "dist")? {
+        Some(dist) => dist,
+        None if required => {
+            return Err(format!("[{strategy_type}] strategy requires a [.dist].").into())
+        }
+        None => "random".to_string(),
+    };
+    HueDistribution::from_name(&dist)
+        .ok_or_else(|| format!("{dist} is not a valid distribution.").into())
+}
+
+// CHROMA STRATEGY
+// ===============
+// This determines the chroma of every colour, once hues and luminescence are decided.
+//
+// STRATEGIES:
+// "random":
+//      each colour gets a random chroma within [range-start] to [range-end].
+//      PARAMETERS: range-start (0 by default), range-end (128 by default)
+//
+// "fixed":
+//      every colour gets the same chroma.
+//      PARAMETERS: chroma
+//
+// "distributed":
+//      chroma is spread across the palette's lightness - the darkest colour gets [range-start],
+//      the lightest gets [range-end], and everything else is in between.
+//      PARAMETERS: range-start (0 by default), range-end (128 by default)
+//
+// "curve":
+//      chroma is tied to lightness - peaking at [peak-lum], and falling off to [min] towards
+//      the extremes. [power] shapes the falloff - higher values make the peak narrower.
+//      PARAMETERS: min (0 by default), peak (100 by default), peak-lum (50 by default),
+//                  power (1 by default)
+//
+// "unified":
+//      every colour of the same hue shares one random chroma.
+//      PARAMETERS: range-start (0 by default), range-end (128 by default)
+//
+// META:
+// clamp = if true, chroma is reduced to the most the sRGB gamut can show for each colour's
+//      lightness and hue, before any other colours are added. (see GENERATION SPACE)
+
+fn read_chroma_strategy(
+    config: &Value,
+    reader: &mut impl ConfigReader,
+) -> GeneratorResult<(ChromaStrategy, bool)> {
+    let chroma_strategy = config
+        .get("chroma-strategy")
+        .ok_or("[chroma-strategy] is required.")?;
+
+    let strategy_name = reader
+        .str(chroma_strategy, "type")?
+        .ok_or("[chroma-strategy.type] must be present.")?;
+    let clamp = yaml_bool(chroma_strategy, "clamp")?;
+
+    let strategy = match strategy_name.as_str() {
+        "random" => ChromaStrategy::Random(non_empty(read_range(chroma_strategy, reader)?)?),
+        "fixed" => ChromaStrategy::Fixed(
+            reader
+                .f64(chroma_strategy, "chroma")?
+                .ok_or("if [chroma-strategy] is [fixed], [chroma] is required.")?,
+        ),
+        "distributed" => ChromaStrategy::Distributed(read_range(chroma_strategy, reader)?),
+        "curve" => ChromaStrategy::Curve {
+            min: reader.f64(chroma_strategy, "min")?.unwrap_or(0.0),
+            peak: reader.f64(chroma_strategy, "peak")?.unwrap_or(100.0),
+            peak_lum: reader.f64(chroma_strategy, "peak-lum")?.unwrap_or(50.0),
+            power: reader.f64(chroma_strategy, "power")?.unwrap_or(1.0),
+        },
+        "unified" => ChromaStrategy::Unified(non_empty(read_range(chroma_strategy, reader)?)?),
+        _ => return Err(format!("{strategy_name} is not a valid chroma_strategy.").into()),
+    };
+
+    reader.state("clamp", &clamp)?;
+
+    Ok((strategy, clamp))
+}
+
+fn read_range(
+    chroma_strategy: &Value,
+    reader: &mut impl ConfigReader,
+) -> GeneratorResult<Range<f64>> {
+    let start = reader.f64(chroma_strategy, "range-start")?.unwrap_or(0.0);
+    let end = reader.f64(chroma_strategy, "range-end")?.unwrap_or(128.0);
+    Ok(start..end)
+}
+
+fn non_empty(range: Range<f64>) -> GeneratorResult<Range<f64>> {
+    match range.is_empty() {
+        true => Err("[chroma-strategy.range-start] must be below [range-end].".into()),
+        false => Ok(range),
+    }
+}
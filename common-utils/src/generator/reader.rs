@@ -0,0 +1,103 @@
+use std::fmt::Display;
+
+use palette::{IntoColor, Lch};
+use rand::RngCore;
+use serde_yaml::Value;
+
+use crate::colour::parse_rgb;
+
+use super::{yaml_f64, yaml_str, yaml_u64, GeneratorResult};
+
+// CONFIG READERS
+// ==============
+// A generator's YAML is walked once, by its own [read] - but each value is read through a
+//  [ConfigReader], which decides what it may look like:
+//  - Exact         - every value has to be given exactly. used by [from_yaml].
+//  - imgtoy's own  - ranges and choices (like `{ min: 0, max: 10 }`) are resolved as they're
+//      read, and everything read is logged.
+//
+// So the schema only lives in one place, and both agree on it.
+
+pub trait ConfigReader {
+    fn f64(&mut self, value: &Value, name: &str) -> GeneratorResult<Option<f64>>;
+    fn u64(&mut self, value: &Value, name: &str) -> GeneratorResult<Option<u64>>;
+    fn f64_list(&mut self, value: &Value, name: &str) -> GeneratorResult<Option<Vec<f64>>>;
+    fn str(&mut self, value: &Value, name: &str) -> GeneratorResult<Option<String>>;
+
+    /// Reads [seed-hue] - a hue, or a colour to take the hue of.
+    fn seed_hue(&mut self, value: &Value) -> GeneratorResult<Option<f64>>;
+
+    /// Randomness needed while reading - such as picking from a pool.
+    fn rng(&mut self) -> &mut dyn RngCore;
+
+    /// Notes something decided while reading, that isn't a value of its own.
+    fn state(&mut self, name: &str, value: &dyn Display) -> GeneratorResult<()>;
+
+    fn begin(&mut self, name: &str) -> GeneratorResult<()>;
+    fn end(&mut self) -> GeneratorResult<()>;
+}
+
+/// Reads every value exactly, as written.
+pub struct Exact<'a> {
+    rng: &'a mut dyn RngCore,
+}
+
+impl<'a> Exact<'a> {
+    pub fn new(rng: &'a mut dyn RngCore) -> Self {
+        Exact { rng }
+    }
+}
+
+impl ConfigReader for Exact<'_> {
+    fn f64(&mut self, value: &Value, name: &str) -> GeneratorResult<Option<f64>> {
+        yaml_f64(value, name)
+    }
+
+    fn u64(&mut self, value: &Value, name: &str) -> GeneratorResult<Option<u64>> {
+        yaml_u64(value, name)
+    }
+
+    fn f64_list(&mut self, value: &Value, name: &str) -> GeneratorResult<Option<Vec<f64>>> {
+        value
+            .get(name)
+            .map(|list| {
+                list.as_sequence()
+                    .and_then(|list| list.iter().map(Value::as_f64).collect())
+                    .ok_or_else(|| format!("[{name}] must be a list of numbers.").into())
+            })
+            .transpose()
+    }
+
+    fn str(&mut self, value: &Value, name: &str) -> GeneratorResult<Option<String>> {
+        Ok(yaml_str(value, name)?.map(String::from))
+    }
+
+    fn seed_hue(&mut self, value: &Value) -> GeneratorResult<Option<f64>> {
+        Ok(match value.get("seed-hue") {
+            None => None,
+            Some(hue) if hue.is_number() => hue.as_f64(),
+            Some(colour) => {
+                let colour = parse_rgb(colour.get("rgb").unwrap_or(colour))
+                    .map_err(|err| format!("[seed-hue] {err}"))?;
+                let colour: Lch = colour.into_color();
+                Some(colour.hue.into_positive_degrees() as f64)
+            }
+        })
+    }
+
+    fn rng(&mut self) -> &mut dyn RngCore {
+        self.rng
+    }
+
+    fn state(&mut self, _name: &str, _value: &dyn Display) -> GeneratorResult<()> {
+        Ok(())
+    }
+
+    fn begin(&mut self, _name: &str) -> GeneratorResult<()> {
+        Ok(())
+    }
+
+    fn end(&mut self) -> GeneratorResult<()> {
+        Ok(())
+    }
+}
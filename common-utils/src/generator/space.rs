@@ -0,0 +1,76 @@
+use palette::{convert::IntoColorUnclamped, rgb::Rgb, Lch, Oklch};
+
+/// Oklch's chroma runs from 0 to ~0.32 rather than LCH's ~128, so it's scaled to match.
+const OKLCH_CHROMA_SCALE: f32 = 1.0 / 400.0;
+
+/// The space random palettes are generated in. Either way, colours are described on LCH's
+/// scales - lightness from 0 to 100, and chroma from 0 to ~128.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GenerationSpace {
+    Lch,
+    Oklch,
+}
+
+impl GenerationSpace {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "lch" => Some(GenerationSpace::Lch),
+            "oklch" => Some(GenerationSpace::Oklch),
+            _ => None,
+        }
+    }
+
+    /// Converts directly, clipping out-of-gamut colours.
+    pub fn to_srgb(&self, colour: Lch) -> Rgb {
+        let (r, g, b) = self.to_srgb_unclamped(colour).into_components();
+        Rgb::new(r.clamp(0.0, 1.0), g.clamp(0.0, 1.0), b.clamp(0.0, 1.0))
+    }
+
+    fn to_srgb_unclamped(&self, colour: Lch) -> Rgb {
+        match self {
+            GenerationSpace::Lch => colour.into_color_unclamped(),
+            GenerationSpace::Oklch => Oklch::new(
+                colour.l / 100.0,
+                colour.chroma * OKLCH_CHROMA_SCALE,
+                colour.hue.into_degrees(),
+            )
+            .into_color_unclamped(),
+        }
+    }
+
+    /// The highest chroma that still fits within sRGB, for the given lightness and hue.
+    pub fn max_chroma(&self, l: f32, hue: f32) -> f32 {
+        let fits = |chroma: f32| {
+            let (r, g, b) = self
+                .to_srgb_unclamped(Lch::new(l, chroma, hue))
+                .into_components();
+            [r, g, b].iter().all(|c| (-0.0001..=1.0001).contains(c))
+        };
+
+        let (mut lo, mut hi) = (0.0f32, 150.0f32);
+        if !fits(lo) {
+            return 0.0;
+        }
+
+        for _ in 0..16 {
+            let mid = (lo + hi) / 2.0;
+            if fits(mid) {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+
+        lo
+    }
+
+    /// Converts, reducing chroma as needed to land within sRGB.
+    pub fn map_to_srgb(&self, colour: Lch) -> Rgb {
+        let max_chroma = self.max_chroma(colour.l, colour.hue.into_degrees());
+        self.to_srgb(Lch::new(
+            colour.l,
+            colour.chroma.min(max_chroma),
+            colour.hue,
+        ))
+    }
+}
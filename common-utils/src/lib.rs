@@ -1,3 +1,6 @@
 pub mod palette;
 pub mod image;
-pub mod effectlog;
\ No newline at end of file
+pub mod effectlog;
+pub mod generator;
+pub mod naming;
+pub mod colour;
\ No newline at end of file
@@ -0,0 +1,289 @@
+use std::sync::OnceLock;
+
+use palette::{color_difference::Ciede2000, rgb::Rgb, IntoColor, Lab, Srgb};
+
+// COLOUR NAMES
+// ============
+// Gives colours human-readable names, so palettes can be described in logs and alt text -
+//  e.g. "deep navy, coral, pale gold" rather than a list of hex codes.
+//
+// Names come from two dictionaries:
+//  - CSS/X11       - the standard named colours, spaced out ("light coral", not "lightcoral").
+//  - EXTENDED      - more descriptive names, filling in the gaps between the standard ones.
+//
+// A colour is given the name whose colour is closest by ΔE (CIEDE2000), so every colour has a
+//  name - even if it's only approximate.
+
+const CSS_X11: &[(&str, u32)] = &[
+    ("alice blue", 0xf0f8ff),
+    ("antique white", 0xfaebd7),
+    ("aquamarine", 0x7fffd4),
+    ("azure", 0xf0ffff),
+    ("beige", 0xf5f5dc),
+    ("bisque", 0xffe4c4),
+    ("black", 0x000000),
+    ("blanched almond", 0xffebcd),
+    ("blue", 0x0000ff),
+    ("blue violet", 0x8a2be2),
+    ("brown", 0xa52a2a),
+    ("burlywood", 0xdeb887),
+    ("cadet blue", 0x5f9ea0),
+    ("chartreuse", 0x7fff00),
+    ("chocolate", 0xd2691e),
+    ("coral", 0xff7f50),
+    ("cornflower blue", 0x6495ed),
+    ("cornsilk", 0xfff8dc),
+    ("crimson", 0xdc143c),
+    ("cyan", 0x00ffff),
+    ("dark blue", 0x00008b),
+    ("dark cyan", 0x008b8b),
+    ("dark goldenrod", 0xb8860b),
+    ("dark grey", 0xa9a9a9),
+    ("dark green", 0x006400),
+    ("dark khaki", 0xbdb76b),
+    ("dark magenta", 0x8b008b),
+    ("dark olive green", 0x556b2f),
+    ("dark orange", 0xff8c00),
+    ("dark orchid", 0x9932cc),
+    ("dark red", 0x8b0000),
+    ("dark salmon", 0xe9967a),
+    ("dark sea green", 0x8fbc8f),
+    ("dark slate blue", 0x483d8b),
+    ("dark slate grey", 0x2f4f4f),
+    ("dark turquoise", 0x00ced1),
+    ("dark violet", 0x9400d3),
+    ("deep pink", 0xff1493),
+    ("deep sky blue", 0x00bfff),
+    ("dim grey", 0x696969),
+    ("dodger blue", 0x1e90ff),
+    ("firebrick", 0xb22222),
+    ("floral white", 0xfffaf0),
+    ("forest green", 0x228b22),
+    ("gainsboro", 0xdcdcdc),
+    ("ghost white", 0xf8f8ff),
+    ("gold", 0xffd700),
+    ("goldenrod", 0xdaa520),
+    ("grey", 0x808080),
+    ("green", 0x008000),
+    ("green yellow", 0xadff2f),
+    ("honeydew", 0xf0fff0),
+    ("hot pink", 0xff69b4),
+    ("indian red", 0xcd5c5c),
+    ("indigo", 0x4b0082),
+    ("ivory", 0xfffff0),
+    ("khaki", 0xf0e68c),
+    ("lavender", 0xe6e6fa),
+    ("lavender blush", 0xfff0f5),
+    ("lawn green", 0x7cfc00),
+    ("lemon chiffon", 0xfffacd),
+    ("light blue", 0xadd8e6),
+    ("light coral", 0xf08080),
+    ("light cyan", 0xe0ffff),
+    ("light goldenrod", 0xeedd82),
+    ("light goldenrod yellow", 0xfafad2),
+    ("light grey", 0xd3d3d3),
+    ("light green", 0x90ee90),
+    ("light pink", 0xffb6c1),
+    ("light salmon", 0xffa07a),
+    ("light sea green", 0x20b2aa),
+    ("light sky blue", 0x87cefa),
+    ("light slate blue", 0x8470ff),
+    ("light slate grey", 0x778899),
+    ("light steel blue", 0xb0c4de),
+    ("light yellow", 0xffffe0),
+    ("lime", 0x00ff00),
+    ("lime green", 0x32cd32),
+    ("linen", 0xfaf0e6),
+    ("magenta", 0xff00ff),
+    ("maroon", 0x800000),
+    ("medium aquamarine", 0x66cdaa),
+    ("medium blue", 0x0000cd),
+    ("medium orchid", 0xba55d3),
+    ("medium purple", 0x9370db),
+    ("medium sea green", 0x3cb371),
+    ("medium slate blue", 0x7b68ee),
+    ("medium spring green", 0x00fa9a),
+    ("medium turquoise", 0x48d1cc),
+    ("medium violet red", 0xc71585),
+    ("midnight blue", 0x191970),
+    ("mint cream", 0xf5fffa),
+    ("misty rose", 0xffe4e1),
+    ("moccasin", 0xffe4b5),
+    ("navajo white", 0xffdead),
+    ("navy", 0x000080),
+    ("old lace", 0xfdf5e6),
+    ("olive", 0x808000),
+    ("olive drab", 0x6b8e23),
+    ("orange", 0xffa500),
+    ("orange red", 0xff4500),
+    ("orchid", 0xda70d6),
+    ("pale goldenrod", 0xeee8aa),
+    ("pale green", 0x98fb98),
+    ("pale turquoise", 0xafeeee),
+    ("pale violet red", 0xdb7093),
+    ("papaya whip", 0xffefd5),
+    ("peach puff", 0xffdab9),
+    ("peru", 0xcd853f),
+    ("pink", 0xffc0cb),
+    ("plum", 0xdda0dd),
+    ("powder blue", 0xb0e0e6),
+    ("purple", 0x800080),
+    ("rebecca purple", 0x663399),
+    ("red", 0xff0000),
+    ("rosy brown", 0xbc8f8f),
+    ("royal blue", 0x4169e1),
+    ("saddle brown", 0x8b4513),
+    ("salmon", 0xfa8072),
+    ("sandy brown", 0xf4a460),
+    ("sea green", 0x2e8b57),
+    ("seashell", 0xfff5ee),
+    ("sienna", 0xa0522d),
+    ("silver", 0xc0c0c0),
+    ("sky blue", 0x87ceeb),
+    ("slate blue", 0x6a5acd),
+    ("slate grey", 0x708090),
+    ("snow", 0xfffafa),
+    ("spring green", 0x00ff7f),
+    ("steel blue", 0x4682b4),
+    ("tan", 0xd2b48c),
+    ("teal", 0x008080),
+    ("thistle", 0xd8bfd8),
+    ("tomato", 0xff6347),
+    ("turquoise", 0x40e0d0),
+    ("violet", 0xee82ee),
+    ("violet red", 0xd02090),
+    ("wheat", 0xf5deb3),
+    ("white", 0xffffff),
+    ("white smoke", 0xf5f5f5),
+    ("yellow", 0xffff00),
+    ("yellow green", 0x9acd32),
+];
+
+const EXTENDED: &[(&str, u32)] = &[
+    ("amber", 0xffbf00),
+    ("apricot", 0xfbceb1),
+    ("ash grey", 0xb2beb5),
+    ("baby blue", 0x89cff0),
+    ("blood red", 0x660000),
+    ("blush", 0xde5d83),
+    ("bone", 0xe3dac9),
+    ("brick red", 0xcb4154),
+    ("bronze", 0xcd7f32),
+    ("bubblegum", 0xffc1cc),
+    ("burgundy", 0x800020),
+    ("burnt orange", 0xcc5500),
+    ("cerulean", 0x007ba7),
+    ("charcoal", 0x36454f),
+    ("cherry", 0xde3163),
+    ("chestnut", 0x954535),
+    ("cobalt", 0x0047ab),
+    ("coffee", 0x6f4e37),
+    ("copper", 0xb87333),
+    ("cream", 0xfffdd0),
+    ("deep green", 0x056608),
+    ("deep navy", 0x0b1a3a),
+    ("deep purple", 0x36013f),
+    ("deep red", 0x850101),
+    ("deep teal", 0x004d4d),
+    ("denim", 0x1560bd),
+    ("dusk blue", 0x26428b),
+    ("dusty rose", 0xc0737a),
+    ("electric blue", 0x7df9ff),
+    ("emerald", 0x50c878),
+    ("espresso", 0x3c2218),
+    ("gunmetal", 0x2a3439),
+    ("hot magenta", 0xff1dce),
+    ("jade", 0x00a86b),
+    ("jet", 0x343434),
+    ("lemon", 0xfff44f),
+    ("lilac", 0xc8a2c8),
+    ("mahogany", 0xc04000),
+    ("mauve", 0xe0b0ff),
+    ("mint", 0x98ff98),
+    ("moss", 0x8a9a5b),
+    ("mustard", 0xe1ad01),
+    ("neon green", 0x39ff14),
+    ("neon pink", 0xff6ec7),
+    ("ochre", 0xcc7722),
+    ("off-white", 0xf4f1ea),
+    ("pale blue", 0xafdbf5),
+    ("pale gold", 0xe6d690),
+    ("pale pink", 0xfadadd),
+    ("pale yellow", 0xffffbf),
+    ("peach", 0xffe5b4),
+    ("periwinkle", 0xccccff),
+    ("petrol", 0x005f6a),
+    ("pine", 0x01796f),
+    ("raspberry", 0xe30b5c),
+    ("rose", 0xff007f),
+    ("rust", 0xb7410e),
+    ("sage", 0x9caf88),
+    ("salmon pink", 0xff91a4),
+    ("sand", 0xc2b280),
+    ("sapphire", 0x0f52ba),
+    ("scarlet", 0xff2400),
+    ("sea foam", 0x93e9be),
+    ("smoke", 0x848884),
+    ("steel grey", 0x71797e),
+    ("storm grey", 0x717486),
+    ("tangerine", 0xf28500),
+    ("teal blue", 0x367588),
+    ("terracotta", 0xe2725b),
+    ("ultramarine", 0x3f00ff),
+    ("vermilion", 0xe34234),
+    ("wine", 0x722f37),
+];
+
+/// Every name, alongside its colour in Lab - converted once, on first use.
+fn dictionary() -> &'static [(&'static str, Lab)] {
+    static DICTIONARY: OnceLock<Vec<(&'static str, Lab)>> = OnceLock::new();
+
+    DICTIONARY.get_or_init(|| {
+        CSS_X11
+            .iter()
+            .chain(EXTENDED)
+            .map(|(name, hex)| {
+                let [_, r, g, b] = hex.to_be_bytes();
+                (*name, Srgb::new(r, g, b).into_format::<f32>().into_color())
+            })
+            .collect()
+    })
+}
+
+/// The name closest to a colour.
+pub fn nearest_name(colour: &Rgb) -> &'static str {
+    let lab: Lab = (*colour).into_color();
+
+    dictionary()
+        .iter()
+        .map(|(name, named)| (*name, lab.difference(*named)))
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(name, _)| name)
+        .unwrap()
+}
+
+/// Describes a palette as a list of names - e.g. "deep navy, coral, pale gold".
+///
+/// Colours sharing a name are only mentioned once. Past `max` names, the rest are summarised
+///  as "and N more".
+pub fn describe_palette(palette: &[Rgb], max: usize) -> String {
+    let mut names: Vec<&str> = vec![];
+    for colour in palette {
+        let name = nearest_name(colour);
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+
+    let mut description = names
+        .iter()
+        .take(max)
+        .copied()
+        .collect::<Vec<_>>()
+        .join(", ");
+    if names.len() > max {
+        description += &format!(" and {} more", names.len() - max);
+    }
+
+    description
+}
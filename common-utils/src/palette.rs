@@ -1,8 +1,11 @@
 use image_effects::{prelude::{SrgbColour as RGB, IntoGradient, IntoGradientLch}, colour::gradient::GradientMethod};
-use palette::{rgb::Rgb, Lch, named, IntoColor};
+use palette::{rgb::Rgb, Lch};
+use image::RgbImage;
 use rand::{rngs::StdRng, Rng};
 
-use crate::effectlog::{ExecLog, LogEntry};
+use crate::effectlog::ExecLog;
+use crate::generator::RandomV1;
+use crate::naming::nearest_name;
 
 pub fn palettes<'a>() -> Vec<(&'a str, Vec<Rgb>)> { 
     const GRADIENT_METHOD: GradientMethod = GradientMethod::LCH;
@@ -268,6 +271,7 @@ pub fn palettes<'a>() -> Vec<(&'a str, Vec<Rgb>)> {
     palettes
 }
 
+/// A palette, along with a log of how it was generated - see [crate::generator::PaletteGenerator].
 pub type LoggedPalette = (Vec<Rgb>, ExecLog);
 
 pub fn generate_n_random_palettes(rng: &mut StdRng, n: usize) -> Vec<LoggedPalette> {
@@ -282,65 +286,68 @@ pub fn generate_n_random_palettes(rng: &mut StdRng, n: usize) -> Vec<LoggedPalet
     palettes
 }
 
-pub fn generate_random_palette(mut rng: &mut impl Rng) -> LoggedPalette {
-    fn gen_with_lightness(rng: &mut impl Rng, min: f32, max: f32) -> Lch {
-        Lch::new(
-            rng.gen_range(min..=max),
-            rng.gen_range(0.0..128.0),
-            rng.gen_range(0.0..360.0),        
-        )
-    }
+/// A random_v1 palette - see [RandomV1].
+pub fn generate_random_palette(rng: &mut impl Rng) -> LoggedPalette {
+    RandomV1.palette(rng)
+}
 
-    let mut palette = vec![
-        gen_with_lightness(rng, 80.0, 100.0),
-        gen_with_lightness(rng, 20.0,  80.0),
-        gen_with_lightness(rng,  0.0,  20.0),
-    ];
+pub fn generate_palette_html(gradient: Vec<Rgb>) -> String {
+    let palette_html = gradient.iter().map(|colour| {
+        let (r, g, b) = colour.into_format::<u8>().into_components();
+        format!("<div title=\"{} (#{})\" style=\"height: 100%; background: rgb({r},{g},{b}); flex-grow: 1; padding:3px;\"></div>", nearest_name(colour), rgb_to_hex(colour))
+    }).collect::<Vec<_>>().concat();
 
-    for _ in 0..rng.gen_range(0..10) {
-        palette.push(gen_with_lightness(&mut rng,  0.0,  100.0));
-    }
+    ["<div style=\"width: 100%; display: flex; flex-wrap: wrap;\">",
+        &palette_html,
+        "</div>"].concat()
+}
 
-    let mut log = ExecLog::new();
-    
-    let mut palette = palette
-        .into_iter()
-        .map(|col| {
-            let col: Rgb = col.into_color();
-            col
-        })
-        .map(|col| {
-            if rng.gen_bool(0.10) {
-                let amnt = rng.gen_range(2..=10);
-                log.add_entry(LogEntry::gradient(col, amnt));
-                col.build_gradient_lch(amnt)
-            } else {
-                log.add_entry(LogEntry::colour(col));
-                vec![col]
-            }
-        })
-        .collect::<Vec<_>>()
-        .concat();
+/// A standalone HTML page showing the palette, with each colour's hex code and nearest name.
+pub fn generate_palette_html_page(name: &str, palette: &[Rgb]) -> String {
+    let hexes = palette.iter().map(|colour| format!("<code>{}</code> {}", rgb_to_hex(colour), nearest_name(colour))).collect::<Vec<_>>().join("<br/>\n");
 
-    if rng.gen_bool(0.75) {
-        palette.push(named::BLACK.into_format());
-        log.add_entry(LogEntry::colour(named::BLACK.into_format()));
-        palette.push(named::WHITE.into_format());
-        log.add_entry(LogEntry::colour(named::WHITE.into_format()));
-    }
+    ["<!DOCTYPE html>\n<html>\n<head><meta charset=\"utf-8\"><title>", name, "</title></head>\n",
+        "<body style=\"font-family: monospace;\">\n<h1>", name, "</h1>\n",
+        "<div style=\"height: 64px;\">", &generate_palette_html(palette.to_vec()), "</div>\n",
+        "<p>", &hexes, "</p>\n</body>\n</html>\n"].concat()
+}
 
-    (palette, log)
+pub fn rgb_to_hex(colour: &Rgb) -> String {
+    let (r, g, b) = colour.into_format::<u8>().into_components();
+    format!("{r:02x}{g:02x}{b:02x}")
 }
 
-pub fn generate_palette_html(gradient: Vec<Rgb>) -> String {
-    let palette_html = gradient.iter().map(|colour| {
+/// The palette as a GIMP palette (.gpl).
+pub fn palette_to_gpl(name: &str, palette: &[Rgb]) -> String {
+    let colours = palette.iter().map(|colour| {
         let (r, g, b) = colour.into_format::<u8>().into_components();
-        format!("<div style=\"height: 100%; background: rgb({r},{g},{b}); flex-grow: 1; padding:3px;\"></div>")
+        format!("{r:>3} {g:>3} {b:>3}\t#{}\n", rgb_to_hex(colour))
     }).collect::<Vec<_>>().concat();
 
-    ["<div style:\"width: 100%; display: flex; flex-wrap: wrap;\">",
-        &palette_html,
-        "</div>"].concat()
+    format!("GIMP Palette\nName: {name}\nColumns: {}\n#\n{colours}", palette.len().min(16))
+}
+
+/// The palette as a list of hex codes (.hex), as used by Lospec.
+pub fn palette_to_hex(palette: &[Rgb]) -> String {
+    palette.iter().map(|colour| rgb_to_hex(colour) + "\n").collect::<Vec<_>>().concat()
+}
+
+/// The palette as JSON - shaped like a "specified" palette, so it can be used as one directly.
+pub fn palette_to_json(palette: &[Rgb]) -> String {
+    let colours = palette.iter()
+        .map(|colour| format!("    {{ \"rgb\": \"{}\" }}", rgb_to_hex(colour)))
+        .collect::<Vec<_>>()
+        .join(",\n");
+
+    format!("{{\n  \"type\": \"specified\",\n  \"colours\": [\n{colours}\n  ]\n}}\n")
+}
+
+/// The palette as a horizontal strip of square swatches.
+pub fn palette_to_swatch_image(palette: &[Rgb], swatch_size: u32) -> RgbImage {
+    RgbImage::from_fn(swatch_size * palette.len() as u32, swatch_size, |x, _| {
+        let (r, g, b) = palette[(x / swatch_size) as usize].into_format::<u8>().into_components();
+        image::Rgb([r, g, b])
+    })
 }
 
 pub fn generate_hue_gradient(hue: f32) -> Vec<Vec<Lch>> {
@@ -1,5 +1,5 @@
 use std::{fs::File, error::Error};
-use common_utils::{palette::generate_random_palette, image::{ImageRequest}, effectlog::{LogEntry, ExecLog}};
+use common_utils::{generator::{load_generator, PaletteGenerator, RandomV1}, image::{ImageRequest}, effectlog::{LogEntry, ExecLog}};
 use image::{codecs::gif::GifEncoder, Frame};
 use image_effects::{prelude::*, dither::bayer::Bayer};
 use palette::named;
@@ -9,14 +9,20 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     const TARGET: &str = "https://media.tenor.com/z7CgyBnsPAYAAAAC/sunset-cool.gif";
 
+    // optionally, a palette config (as YAML) can be passed - random_v1 is used otherwise.
+    let generator: Box<dyn PaletteGenerator> = match std::env::args().nth(1) {
+        Some(path) => load_generator(path)?,
+        None => Box::new(RandomV1),
+    };
+
     let frames = ImageRequest::new(TARGET.into())
         .gif()
         .url()
         .perform()?
         .into_gif()?;
 
-    generate_gifs_with_n_random_palettes(frames, 250)?;
-    // generate_gifs_with_alternating_palettes(frames, 250)?;
+    generate_gifs_with_n_random_palettes(frames, generator.as_ref(), 250)?;
+    // generate_gifs_with_alternating_palettes(frames, generator.as_ref(), 250)?;
 
     // for (name, palette) in palettes {
     //     println!("working for palette: {name}");
@@ -39,7 +45,7 @@ fn main() -> Result<(), Box<dyn Error>> {
     Ok(())
 }   
 
-fn generate_gifs_with_alternating_palettes(frames: Vec<Frame>, n: usize) -> Result<(), Box<dyn Error>> {
+fn generate_gifs_with_alternating_palettes(frames: Vec<Frame>, generator: &dyn PaletteGenerator, n: usize) -> Result<(), Box<dyn Error>> {
     let mut rng = StdRng::from_entropy();
 
     for i in 1..n {
@@ -82,7 +88,7 @@ fn generate_gifs_with_alternating_palettes(frames: Vec<Frame>, n: usize) -> Resu
         log.add_entry(LogEntry::effect(format!("{} generated palettes.", frames.len()), "[omitted]".to_string()));
 
         let frames = frames.clone().into_iter().map(|mut frame| {
-            let (palette, _) = generate_random_palette(&mut rng);
+            let palette = generator.generate(&mut rng)?;
 
             let palette = [palette, vec![named::BLACK.into_format(), named::WHITE.into_format()]].concat();
             effects.push(Box::new(Bayer::new(*matrix_size as usize, palette)));
@@ -92,8 +98,8 @@ fn generate_gifs_with_alternating_palettes(frames: Vec<Frame>, n: usize) -> Resu
             }
 
             effects.pop();
-            frame
-        }).collect::<Vec<_>>();
+            Ok(frame)
+        }).collect::<Result<Vec<_>, Box<dyn Error>>>()?;
 
         let file_out = File::create(format!("./gif-effect/data/gen-output-{i}.gif")).unwrap();
         let mut encoder = GifEncoder::new(file_out);
@@ -106,13 +112,13 @@ fn generate_gifs_with_alternating_palettes(frames: Vec<Frame>, n: usize) -> Resu
     Ok(())
 }
 
-fn generate_gifs_with_n_random_palettes(frames: Vec<Frame>, n: usize) -> Result<(), Box<dyn Error>> {
+fn generate_gifs_with_n_random_palettes(frames: Vec<Frame>, generator: &dyn PaletteGenerator, n: usize) -> Result<(), Box<dyn Error>> {
     let mut rng = StdRng::from_entropy();
 
     for i in 0..n {
         println!("palette {i} / {n}");
 
-        let (palette, mut log) = generate_random_palette(&mut rng);
+        let (palette, mut log) = generator.generate_logged(&mut rng)?;
 
         let mut effects: Vec<Box<dyn Effect<Frame>>> = Vec::new();
 
@@ -0,0 +1,241 @@
+use serde_yaml::Mapping;
+
+use crate::effects::BaseResult;
+
+// EXPRESSIONS
+// ===========
+// Small arithmetic expressions, such as `$base_hue + 180` or `($a + $b) / 2`.
+//
+// Supported are numbers, variables (`$name`), parentheses, unary minus,
+//  and the `+`, `-`, `*`, `/` and `%` operators with the usual precedence.
+// Variables are looked up in the already-resolved [vars] of the iteration.
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Variable(String),
+    Operator(char),
+    Open,
+    Close,
+}
+
+/// Returns whether `text` references any variables, and so should be evaluated.
+pub fn is_expression(text: &str) -> bool {
+    text.char_indices().any(|(i, c)| {
+        c == '$'
+            && text[i + 1..]
+                .chars()
+                .next()
+                .is_some_and(|next| next.is_alphabetic() || next == '_')
+    })
+}
+
+pub fn evaluate(text: &str, vars: &Mapping) -> BaseResult<f64> {
+    let tokens = tokenize(text)?;
+    let mut parser = Parser {
+        text,
+        tokens: &tokens,
+        position: 0,
+        vars,
+    };
+
+    let value = parser.expression()?;
+
+    if parser.position != tokens.len() {
+        return Err(format!("unexpected trailing input in expression [{text}].").into());
+    }
+
+    Ok(value)
+}
+
+fn tokenize(text: &str) -> BaseResult<Vec<Token>> {
+    let mut tokens = vec![];
+    let mut chars = text.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            ' ' | '\t' => {
+                chars.next();
+            }
+            '+' | '-' | '*' | '/' | '%' => {
+                tokens.push(Token::Operator(c));
+                chars.next();
+            }
+            '(' => {
+                tokens.push(Token::Open);
+                chars.next();
+            }
+            ')' => {
+                tokens.push(Token::Close);
+                chars.next();
+            }
+            '$' => {
+                chars.next();
+                let mut name = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_alphanumeric() || c == '_' {
+                        name.push(c);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                if name.is_empty() {
+                    return Err(format!("expected a variable name after [$] in [{text}].").into());
+                }
+                tokens.push(Token::Variable(name));
+            }
+            c if c.is_ascii_digit() || c == '.' => {
+                let mut number = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_ascii_digit() || c == '.' {
+                        number.push(c);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                let number = number
+                    .parse::<f64>()
+                    .map_err(|_| format!("[{number}] is not a valid number in [{text}]."))?;
+                tokens.push(Token::Number(number));
+            }
+            _ => return Err(format!("unexpected character [{c}] in expression [{text}].").into()),
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    tokens: &'a [Token],
+    position: usize,
+    vars: &'a Mapping,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    // expression = term (("+" | "-") term)*
+    fn expression(&mut self) -> BaseResult<f64> {
+        let mut value = self.term()?;
+
+        while let Some(Token::Operator(op @ ('+' | '-'))) = self.peek().cloned() {
+            self.next();
+            let rhs = self.term()?;
+            value = if op == '+' { value + rhs } else { value - rhs };
+        }
+
+        Ok(value)
+    }
+
+    // term = factor (("*" | "/" | "%") factor)*
+    fn term(&mut self) -> BaseResult<f64> {
+        let mut value = self.factor()?;
+
+        while let Some(Token::Operator(op @ ('*' | '/' | '%'))) = self.peek().cloned() {
+            self.next();
+            let rhs = self.factor()?;
+            value = match op {
+                '*' => value * rhs,
+                '/' => value / rhs,
+                _ => value.rem_euclid(rhs),
+            };
+        }
+
+        Ok(value)
+    }
+
+    // factor = number | variable | "(" expression ")" | "-" factor
+    fn factor(&mut self) -> BaseResult<f64> {
+        let text = self.text;
+        match self.next() {
+            Some(Token::Number(number)) => Ok(number),
+            Some(Token::Variable(name)) => self
+                .vars
+                .get(name.as_str())
+                .and_then(|value| value.as_f64())
+                .ok_or_else(|| format!("unknown variable [${name}] in [{text}].").into()),
+            Some(Token::Operator('-')) => Ok(-self.factor()?),
+            Some(Token::Open) => {
+                let value = self.expression()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(value),
+                    _ => Err(format!("expected a closing [)] in [{text}].").into()),
+                }
+            }
+            _ => Err(format!("incomplete expression [{text}].").into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_yaml::{Mapping, Value};
+
+    use super::{evaluate, is_expression};
+
+    fn vars() -> Mapping {
+        let mut vars = Mapping::new();
+        vars.insert("a".into(), Value::from(10.0));
+        vars.insert("b".into(), Value::from(4.0));
+        vars
+    }
+
+    fn eval(text: &str) -> f64 {
+        evaluate(text, &vars()).unwrap()
+    }
+
+    fn error(text: &str) -> String {
+        evaluate(text, &vars()).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn precedence() {
+        assert_eq!(eval("1 + 2 * 3"), 7.0);
+        assert_eq!(eval("(1 + 2) * 3"), 9.0);
+        assert_eq!(eval("10 - 4 - 3"), 3.0);
+        assert_eq!(eval("12 / 3 / 2"), 2.0);
+        assert_eq!(eval("2 + 7 % 3"), 3.0);
+        assert_eq!(eval("-2 * 3 + 1"), -5.0);
+        assert_eq!(eval("-(2 + 3)"), -5.0);
+    }
+
+    #[test]
+    fn variables() {
+        assert_eq!(eval("$a + $b / 2"), 12.0);
+        assert_eq!(eval("($a + $b) / 2"), 7.0);
+        assert_eq!(eval("$a - 20 % 360"), -10.0);
+        assert_eq!(eval("($b - $a) % 360"), 354.0);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(error("1 +").contains("incomplete expression"));
+        assert!(error("(1 + 2").contains("expected a closing [)]"));
+        assert!(error("1 2").contains("unexpected trailing input"));
+        assert!(error("1 + )").contains("incomplete expression"));
+        assert!(error("$ + 1").contains("expected a variable name"));
+        assert!(error("1 & 2").contains("unexpected character [&]"));
+        assert!(error("1.2.3").contains("[1.2.3] is not a valid number"));
+        assert!(error("$c * 2").contains("unknown variable [$c]"));
+    }
+
+    #[test]
+    fn detects_expressions() {
+        assert!(is_expression("$a + 180"));
+        assert!(is_expression("($_hue)"));
+        assert!(!is_expression("180"));
+        assert!(!is_expression("$5"));
+        assert!(!is_expression("costs $"));
+    }
+}
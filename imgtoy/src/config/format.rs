@@ -0,0 +1,124 @@
+use std::path::Path;
+
+use serde_yaml::Value;
+
+use crate::effects::BaseResult;
+
+// FORMATS
+// =======
+// Configurations can be written in YAML, JSON or TOML - picked by the file's extension.
+//
+// Whatever the format, the file is read into the same YAML value, so everything after
+//  (includes, migration, presets, vars, effects...) behaves identically, and reports
+//  the same `[path] must be ...` errors.
+//
+// Files without a recognised extension are read as YAML.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Yaml,
+    Json,
+    Toml,
+}
+
+impl Format {
+    /// Picks the format from the extension of `path`, defaulting to YAML.
+    pub fn from_path(path: impl AsRef<Path>) -> Self {
+        match path
+            .as_ref()
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_lowercase)
+            .as_deref()
+        {
+            Some("json") => Format::Json,
+            Some("toml") => Format::Toml,
+            _ => Format::Yaml,
+        }
+    }
+
+    /// Picks the format from a name such as `json`, as used within configurations.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "yaml" | "yml" => Some(Format::Yaml),
+            "json" => Some(Format::Json),
+            "toml" => Some(Format::Toml),
+            _ => None,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Format::Yaml => "yaml",
+            Format::Json => "json",
+            Format::Toml => "toml",
+        }
+    }
+
+    pub fn parse(&self, contents: &str) -> BaseResult<Value> {
+        Ok(match self {
+            Format::Yaml => serde_yaml::from_str(contents)?,
+            Format::Json => serde_json::from_str(contents)?,
+            Format::Toml => toml::from_str(contents)?,
+        })
+    }
+
+    pub fn write(&self, value: &Value) -> BaseResult<String> {
+        Ok(match self {
+            Format::Yaml => serde_yaml::to_string(value)?,
+            Format::Json => serde_json::to_string_pretty(value)? + "\n",
+            // TOML has no null, so any unset fields are dropped instead.
+            Format::Toml => toml::to_string_pretty(&without_nulls(value.clone()))?,
+        })
+    }
+}
+
+/// Reads the configuration file at `path`, in whichever format its extension implies.
+pub fn read_config(path: impl AsRef<Path>) -> BaseResult<Value> {
+    let path = path.as_ref();
+    let format = Format::from_path(path);
+    let contents = std::fs::read_to_string(path)
+        .map_err(|err| format!("could not read config file [{}]: {err}", path.display()))?;
+
+    format.parse(&contents).map_err(|err| {
+        format!(
+            "could not parse config file [{}] as {}: {err}",
+            path.display(),
+            format.extension()
+        )
+        .into()
+    })
+}
+
+/// Writes `value` to `path`, in whichever format its extension implies.
+pub fn write_config(path: impl AsRef<Path>, value: &Value) -> BaseResult<()> {
+    let path = path.as_ref();
+    let format = Format::from_path(path);
+    let contents = format.write(value).map_err(|err| {
+        format!(
+            "could not write config file [{}] as {}: {err}",
+            path.display(),
+            format.extension()
+        )
+    })?;
+
+    std::fs::write(path, contents)?;
+
+    Ok(())
+}
+
+fn without_nulls(value: Value) -> Value {
+    match value {
+        Value::Mapping(mapping) => Value::Mapping(
+            mapping
+                .into_iter()
+                .filter(|(_, value)| !value.is_null())
+                .map(|(key, value)| (key, without_nulls(value)))
+                .collect(),
+        ),
+        Value::Sequence(sequence) => {
+            Value::Sequence(sequence.into_iter().map(without_nulls).collect())
+        }
+        value => value,
+    }
+}
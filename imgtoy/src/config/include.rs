@@ -0,0 +1,150 @@
+use std::path::{Path, PathBuf};
+
+use serde_yaml::Value;
+
+use crate::{
+    config::{format::read_config, merge, migrate::migrate},
+    effects::BaseResult,
+};
+
+// INCLUDES
+// ========
+// A configuration can pull in other files via a top-level [include],
+//  which is either a single path or a list of paths.
+// Paths are relative to the file that includes them.
+//
+// Included files are merged in order, and the including file is merged last -
+//  so anything it specifies overrides what it included.
+// Mappings (such as [presets]) are merged key-by-key, while everything else
+//  (such as [effects]) is replaced outright.
+//
+// Included files can be in any supported format, regardless of the including file's.
+// Included files may include other files themselves, but cycles are rejected.
+// Every file is migrated to the current version on its own, before being merged.
+// Any [palette-library] paths are relative to the file that lists them, too - as are the
+//  paths of palette files (`type: file`) and images to extract palettes from (`type: extract`),
+//  anywhere in a file's effects or presets.
+pub fn load_with_includes(
+    path: &Path,
+    stack: &mut Vec<PathBuf>,
+    warnings: &mut Vec<String>,
+) -> BaseResult<Value> {
+    let canonical = path
+        .canonicalize()
+        .map_err(|err| format!("could not find config file [{}]: {err}", path.display()))?;
+
+    if stack.contains(&canonical) {
+        let cycle = stack
+            .iter()
+            .chain([&canonical])
+            .map(|path| path.display().to_string())
+            .collect::<Vec<_>>()
+            .join(" -> ");
+        return Err(format!("include cycle detected: {cycle}").into());
+    }
+
+    let config = read_config(&canonical)?;
+
+    let mut file_warnings = vec![];
+    let mut config = migrate(config, &mut file_warnings)?;
+    warnings.extend(
+        file_warnings
+            .into_iter()
+            .map(|warning| format!("{}: {warning}", canonical.display())),
+    );
+
+    let parent = canonical
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
+
+    resolve_library_paths(&mut config, &parent);
+    resolve_palette_paths(&mut config, &parent);
+
+    let includes = match config
+        .as_mapping_mut()
+        .and_then(|root| root.shift_remove("include"))
+    {
+        None => vec![],
+        Some(Value::String(include)) => vec![include],
+        Some(Value::Sequence(includes)) => includes
+            .into_iter()
+            .map(|include| match include {
+                Value::String(include) => include,
+                _ => panic!("[include] entries must be paths."),
+            })
+            .collect(),
+        Some(_) => panic!("[include] must be a path, or a list of paths."),
+    };
+
+    if includes.is_empty() {
+        return Ok(config);
+    }
+
+    stack.push(canonical);
+
+    let mut merged = Value::Null;
+    for include in includes {
+        let included = load_with_includes(&parent.join(include), stack, warnings)?;
+        merged = merge(merged, included);
+    }
+
+    stack.pop();
+
+    Ok(merge(merged, config))
+}
+
+/// Makes the [palette-library] paths of a file relative to the file itself, rather than
+/// wherever imgtoy happens to be run from.
+fn resolve_library_paths(config: &mut Value, dir: &Path) {
+    let resolve = |path: &mut Value| {
+        if let Value::String(relative) = path {
+            *relative = dir.join(&relative).display().to_string();
+        }
+    };
+
+    match config.get_mut("palette-library") {
+        Some(Value::Sequence(paths)) => paths.iter_mut().for_each(resolve),
+        Some(path) => resolve(path),
+        None => {}
+    }
+}
+
+/// Makes the paths of palette files (`type: file`) and images to extract from (`type: extract`)
+/// relative to the file that uses them - wherever they are, so palettes nested in presets or
+/// other effects are covered too.
+fn resolve_palette_paths(config: &mut Value, dir: &Path) {
+    match config {
+        Value::Mapping(mapping) => {
+            match mapping.get("type").and_then(Value::as_str) {
+                Some("file") => {
+                    if let Some(Value::String(path)) = mapping.get_mut("path") {
+                        *path = dir.join(&path).display().to_string();
+                    }
+                }
+                // `source` and urls aren't paths, so they're left alone.
+                Some("extract") => {
+                    if let Some(Value::String(from)) = mapping.get_mut("from") {
+                        let is_path = from != "source"
+                            && !from.starts_with("http://")
+                            && !from.starts_with("https://");
+                        if is_path {
+                            *from = dir.join(&from).display().to_string();
+                        }
+                    }
+                }
+                _ => {}
+            }
+
+            for (_, value) in mapping.iter_mut() {
+                resolve_palette_paths(value, dir);
+            }
+        }
+        Value::Sequence(sequence) => {
+            for value in sequence {
+                resolve_palette_paths(value, dir);
+            }
+        }
+        _ => {}
+    }
+}
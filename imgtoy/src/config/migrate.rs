@@ -0,0 +1,227 @@
+use serde_yaml::{Mapping, Value};
+
+use crate::effects::BaseResult;
+
+// MIGRATIONS
+// ==========
+// Configurations carry a top-level [version]. Ones without it are assumed to be version 1.
+//
+// Older configurations are rewritten into the current shape step by step, with a warning
+//  for every rewrite - so they keep working, and can be upgraded for good via
+//  `imgtoy migrate <config> [output]`.
+//
+// VERSION 1 -> 2:
+//  - simple filters took their value directly, e.g. `contrast: 0.5`.
+//      now it's `contrast: { factor: 0.5 }`.
+//  - quantize-hue took its list of hues directly.
+//      now it's `quantize-hue: { hues: [...] }`.
+//  - bayer was its own effect, e.g. `bayer: { matrix-size: 8, palette: ... }`.
+//      now it's an [ordered] effect with a single [bayer] strategy.
+//  - palettes could be the string "random".
+//      now it's `palette: { type: random_v1 }`.
+//
+// Every effect is rewritten - including those within combinators (one-of, shuffle, repeat
+//  and group) and [presets]. Included files are migrated on their own, the same way.
+
+pub const CURRENT_VERSION: u64 = 2;
+
+type Migration = fn(Value, &mut Vec<String>) -> Value;
+
+/// Each entry migrates from version N+1 to N+2.
+const MIGRATIONS: [Migration; 1] = [v1_to_v2];
+
+const SIMPLE_FILTERS: [&str; 5] = [
+    "hue-rotate",
+    "contrast",
+    "brighten",
+    "saturate",
+    "multiply-hue",
+];
+
+/// Migrates `config` to the current version, pushing a warning for every rewrite.
+pub fn migrate(config: Value, warnings: &mut Vec<String>) -> BaseResult<Value> {
+    let version = config.get("version").map_or(1, |version| {
+        version
+            .as_u64()
+            .expect("[version] must be a positive integer.")
+    });
+
+    if version == 0 || version > CURRENT_VERSION {
+        return Err(format!(
+            "[version] {version} is not supported - the current version is {CURRENT_VERSION}."
+        )
+        .into());
+    }
+
+    let mut config = config;
+    let mut rewrites = vec![];
+
+    for migration in &MIGRATIONS[(version - 1) as usize..] {
+        config = migration(config, &mut rewrites);
+    }
+
+    if !rewrites.is_empty() {
+        warnings.push(format!(
+            "configuration is version {version} - migrated to version {CURRENT_VERSION}."
+        ));
+        warnings.extend(rewrites);
+    }
+
+    // the version is kept as the first key, so it's easy to spot in migrated files.
+    Ok(match config {
+        Value::Mapping(mut root) => {
+            root.shift_remove("version");
+            let mut versioned = Mapping::from_iter([("version".into(), CURRENT_VERSION.into())]);
+            versioned.extend(root);
+            Value::Mapping(versioned)
+        }
+        config => config,
+    })
+}
+
+fn v1_to_v2(config: Value, warnings: &mut Vec<String>) -> Value {
+    let mut config = config;
+
+    if let Some(effects) = config
+        .get_mut("effects")
+        .and_then(|effects| effects.as_sequence_mut())
+    {
+        v1_to_v2_effects(effects, "effects", warnings);
+    }
+
+    // presets can hold effects too - as lists, single effects, or bodies with [effects].
+    if let Some(presets) = config
+        .get_mut("presets")
+        .and_then(|presets| presets.as_mapping_mut())
+    {
+        for (name, preset) in presets.iter_mut() {
+            let path = format!("presets.{}", name.as_str().unwrap_or_default());
+
+            if let Some(effects) = preset.as_sequence_mut() {
+                v1_to_v2_effects(effects, &path, warnings);
+            } else if let Some(effects) = preset
+                .get_mut("effects")
+                .and_then(|effects| effects.as_sequence_mut())
+            {
+                v1_to_v2_effects(effects, &format!("{path}.effects"), warnings);
+            } else if is_preset_effect(preset) {
+                v1_to_v2_effect(preset, &path, warnings);
+            }
+        }
+    }
+
+    config
+}
+
+/// Whether a preset is a single effect - a mapping with only the effect's name.
+///
+/// A lone [bayer] without a [palette] is left alone, as it's more likely an ordered strategy.
+fn is_preset_effect(preset: &Value) -> bool {
+    let Some(mapping) = preset.as_mapping() else {
+        return false;
+    };
+    match mapping.iter().next() {
+        Some((name, param)) if mapping.len() == 1 => {
+            name.as_str() != Some("bayer") || param.get("palette").is_some()
+        }
+        _ => false,
+    }
+}
+
+fn v1_to_v2_effects(effects: &mut [Value], path: &str, warnings: &mut Vec<String>) {
+    for (i, effect) in effects.iter_mut().enumerate() {
+        v1_to_v2_effect(effect, &format!("{path}.{i}"), warnings);
+    }
+}
+
+fn v1_to_v2_effect(effect: &mut Value, path: &str, warnings: &mut Vec<String>) {
+    let Some(mapping) = effect.as_mapping_mut() else {
+        return;
+    };
+    let Some((name, param)) = mapping
+        .iter_mut()
+        .next()
+        .and_then(|(name, param)| name.as_str().map(|name| (name.to_string(), param)))
+    else {
+        return;
+    };
+
+    // combinators hold effects of their own.
+    match name.as_str() {
+        "one-of" => {
+            for (i, option) in param.as_sequence_mut().into_iter().flatten().enumerate() {
+                let option_path = format!("{path}.one-of.{i}");
+                if let Some(effects) = option.as_sequence_mut() {
+                    v1_to_v2_effects(effects, &option_path, warnings);
+                } else if let Some(effects) = option
+                    .get_mut("effects")
+                    .and_then(|effects| effects.as_sequence_mut())
+                {
+                    v1_to_v2_effects(effects, &format!("{option_path}.effects"), warnings);
+                }
+            }
+        }
+        "shuffle" => {
+            if let Some(effects) = param.as_sequence_mut() {
+                v1_to_v2_effects(effects, &format!("{path}.shuffle"), warnings);
+            }
+        }
+        "repeat" | "group" => {
+            if let Some(effects) = param
+                .get_mut("effects")
+                .and_then(|effects| effects.as_sequence_mut())
+            {
+                v1_to_v2_effects(effects, &format!("{path}.{name}.effects"), warnings);
+            }
+        }
+        _ => {}
+    }
+
+    if SIMPLE_FILTERS.contains(&name.as_str()) && param.get("factor").is_none() {
+        let factor = param.clone();
+        *param = Value::Mapping(Mapping::from_iter([("factor".into(), factor)]));
+        warnings.push(format!(
+            "[{path}.{name}] now takes its value as [{name}.factor]."
+        ));
+    } else if name == "quantize-hue" && param.is_sequence() {
+        let hues = param.clone();
+        *param = Value::Mapping(Mapping::from_iter([("hues".into(), hues)]));
+        warnings.push(format!(
+            "[{path}.quantize-hue] now takes its hues as [quantize-hue.hues]."
+        ));
+    }
+
+    if let Some(palette) = param.get_mut("palette") {
+        if palette.as_str() == Some("random") {
+            *palette = Value::Mapping(Mapping::from_iter([("type".into(), "random_v1".into())]));
+            warnings.push(format!(
+                "[{path}.{name}.palette] \"random\" is now [palette.type] random_v1."
+            ));
+        }
+    }
+
+    if name == "bayer" {
+        let mut bayer = param.as_mapping().cloned().unwrap_or_default();
+        let palette = bayer.shift_remove("palette");
+
+        let mut ordered = Mapping::new();
+        if let Some(palette) = palette {
+            ordered.insert("palette".into(), palette);
+        }
+        ordered.insert(
+            "strategies".into(),
+            Value::Sequence(vec![Value::Mapping(Mapping::from_iter([(
+                "bayer".into(),
+                Value::Mapping(bayer),
+            )]))]),
+        );
+
+        *effect = Value::Mapping(Mapping::from_iter([(
+            "ordered".into(),
+            Value::Mapping(ordered),
+        )]));
+        warnings.push(format!(
+            "[{path}.bayer] is now an [ordered] effect with a [bayer] strategy."
+        ));
+    }
+}
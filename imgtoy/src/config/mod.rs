@@ -0,0 +1,51 @@
+use std::path::Path;
+
+use serde_yaml::Value;
+
+use crate::effects::BaseResult;
+
+pub mod expression;
+pub mod format;
+pub mod include;
+pub mod migrate;
+pub mod preset;
+pub mod vars;
+
+/// A fully loaded configuration, alongside any warnings raised while loading it.
+pub struct LoadedConfig {
+    pub config: Value,
+    pub warnings: Vec<String>,
+}
+
+/// Loads the configuration at `path` (as YAML, JSON or TOML), migrating it to the current version and resolving
+/// any `include`s, `use`d presets and `${ENV}` placeholders.
+///
+/// `vars` are left in place, since they're resolved once per iteration.
+pub fn load_config(path: impl AsRef<Path>) -> BaseResult<LoadedConfig> {
+    let mut warnings = vec![];
+    let config = include::load_with_includes(path.as_ref(), &mut vec![], &mut warnings)?;
+    let config = preset::resolve_presets(config)?;
+    let config = vars::interpolate_env(config)?;
+
+    Ok(LoadedConfig { config, warnings })
+}
+
+/// Deep-merges `overlay` on top of `base`.
+///
+/// Mappings are merged key-by-key - anything else in `overlay` replaces `base` outright.
+pub fn merge(base: Value, overlay: Value) -> Value {
+    match (base, overlay) {
+        (Value::Mapping(mut base), Value::Mapping(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => *existing = merge(existing.clone(), value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+            Value::Mapping(base)
+        }
+        (_, overlay) => overlay,
+    }
+}
@@ -0,0 +1,84 @@
+use serde_yaml::{Mapping, Value};
+
+use crate::{config::merge, effects::BaseResult};
+
+// PRESETS
+// =======
+// A top-level [presets] mapping defines named fragments - effects, palettes,
+//  ordered strategies, or anything else really.
+//
+// Anywhere in the configuration, a mapping with a [use] key is replaced by the
+//  preset of that name. Any other keys in that mapping are merged on top of the
+//  preset, so individual fields can be overridden:
+//
+//      palette:
+//        use: sunset
+//        config:
+//          lum-strategy: { count: 6 }
+//
+// Presets can [use] other presets, but cycles are rejected.
+pub fn resolve_presets(config: Value) -> BaseResult<Value> {
+    let mut config = config;
+
+    let presets = match config
+        .as_mapping_mut()
+        .and_then(|root| root.shift_remove("presets"))
+    {
+        None => Mapping::new(),
+        Some(Value::Mapping(presets)) => presets,
+        Some(_) => panic!("[presets] must be a mapping of names to presets."),
+    };
+
+    resolve_value(config, &presets, &mut vec![])
+}
+
+fn resolve_value(value: Value, presets: &Mapping, stack: &mut Vec<String>) -> BaseResult<Value> {
+    Ok(match value {
+        Value::Mapping(mut mapping) => {
+            if let Some(name) = mapping.shift_remove("use") {
+                let name = name
+                    .as_str()
+                    .expect("[use] must be the name of a preset.")
+                    .to_string();
+
+                let preset = resolve_preset(&name, presets, stack)?;
+                let overrides = resolve_value(Value::Mapping(mapping), presets, stack)?;
+
+                merge(preset, overrides)
+            } else {
+                let mut resolved = Mapping::new();
+                for (key, value) in mapping {
+                    resolved.insert(key, resolve_value(value, presets, stack)?);
+                }
+                Value::Mapping(resolved)
+            }
+        }
+        Value::Sequence(sequence) => Value::Sequence(
+            sequence
+                .into_iter()
+                .map(|value| resolve_value(value, presets, stack))
+                .collect::<BaseResult<Vec<_>>>()?,
+        ),
+        value => value,
+    })
+}
+
+fn resolve_preset(name: &str, presets: &Mapping, stack: &mut Vec<String>) -> BaseResult<Value> {
+    if stack.iter().any(|entry| entry == name) {
+        let cycle = [stack.as_slice(), &[name.to_string()]]
+            .concat()
+            .join(" -> ");
+        return Err(format!("preset cycle detected: {cycle}").into());
+    }
+
+    let preset = presets
+        .get(name)
+        .ok_or_else(|| format!("[use] refers to unknown preset [{name}]."))?
+        .clone();
+
+    stack.push(name.to_string());
+    let preset = resolve_value(preset, presets, stack)?;
+    stack.pop();
+
+    Ok(preset)
+}
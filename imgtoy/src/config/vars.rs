@@ -0,0 +1,208 @@
+use rand::Rng;
+use serde_yaml::{Mapping, Value};
+
+use crate::{
+    config::expression::{evaluate, is_expression},
+    effects::{BaseResult, Log},
+    parsers::util::logless::parse_f64_complex,
+};
+
+// VARIABLES
+// =========
+// A top-level [vars] mapping defines values that are resolved ONCE per iteration,
+//  and can then be referenced as `$name` anywhere in [effects]:
+//
+//      vars:
+//        base_hue: { min: 0.0, max: 360.0 }
+//        opposite: $base_hue + 180
+//      effects:
+//        - hue-rotate: { factor: $base_hue }
+//        - quantize-hue: { hues: [$base_hue, $opposite] }
+//
+// Each variable is either a complex value (exact, range or choice), or an expression.
+// Variables are resolved in order, so they can refer to the ones defined before them.
+//
+// Separately, `${NAME}` is replaced by the environment variable NAME when loading the
+//  configuration. `${NAME:-default}` falls back to "default" when NAME isn't set.
+
+/// Replaces any `${NAME}` in strings with the matching environment variable.
+///
+/// If the placeholder is the entire string, the result is re-read as YAML - so numbers
+/// and booleans end up with the right type.
+pub fn interpolate_env(value: Value) -> BaseResult<Value> {
+    Ok(match value {
+        Value::String(text) if text.contains("${") => {
+            let interpolated = interpolate_env_str(&text)?;
+            let is_whole = text.starts_with("${") && text.find('}') == Some(text.len() - 1);
+            if is_whole {
+                serde_yaml::from_str(&interpolated).unwrap_or(Value::String(interpolated))
+            } else {
+                Value::String(interpolated)
+            }
+        }
+        Value::Mapping(mapping) => Value::Mapping(
+            mapping
+                .into_iter()
+                .map(|(key, value)| Ok((key, interpolate_env(value)?)))
+                .collect::<BaseResult<Mapping>>()?,
+        ),
+        Value::Sequence(sequence) => Value::Sequence(
+            sequence
+                .into_iter()
+                .map(interpolate_env)
+                .collect::<BaseResult<Vec<_>>>()?,
+        ),
+        value => value,
+    })
+}
+
+fn interpolate_env_str(text: &str) -> BaseResult<String> {
+    let mut result = String::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find("${") {
+        result.push_str(&rest[..start]);
+
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| format!("unclosed [${{] in [{text}]."))?
+            + start;
+
+        let placeholder = &rest[start + 2..end];
+        let (name, default) = match placeholder.split_once(":-") {
+            Some((name, default)) => (name, Some(default)),
+            None => (placeholder, None),
+        };
+
+        let value = match (std::env::var(name), default) {
+            (Ok(value), _) => value,
+            (Err(_), Some(default)) => default.to_string(),
+            (Err(_), None) => {
+                return Err(format!("environment variable [{name}] is not set.").into());
+            }
+        };
+
+        result.push_str(&value);
+        rest = &rest[end + 1..];
+    }
+
+    result.push_str(rest);
+
+    Ok(result)
+}
+
+/// Resolves `vars` for one iteration, and substitutes them into the rest of the configuration.
+pub fn resolve_vars(log: Log, rng: &mut impl Rng, config: &Value) -> BaseResult<Value> {
+    let mut config = config.clone();
+
+    let definitions = match config
+        .as_mapping_mut()
+        .and_then(|root| root.shift_remove("vars"))
+    {
+        None => return Ok(config),
+        Some(Value::Mapping(definitions)) => definitions,
+        Some(_) => panic!("[vars] must be a mapping of names to values."),
+    };
+
+    log.begin_category("vars")?;
+
+    let mut vars = Mapping::new();
+
+    for (name, definition) in definitions {
+        let name = name
+            .as_str()
+            .expect("[vars] names must be strings.")
+            .to_string();
+
+        let resolved = match substitute(definition, &vars)? {
+            Value::String(expression) => evaluate(&expression, &vars)?,
+            definition => parse_f64_complex(rng, &definition),
+        };
+
+        log.state_property(&name, resolved)?;
+        vars.insert(Value::String(name), Value::from(resolved));
+    }
+
+    log.end_category()?;
+
+    substitute(config, &vars)
+}
+
+fn substitute(value: Value, vars: &Mapping) -> BaseResult<Value> {
+    Ok(match value {
+        Value::String(text) if is_expression(&text) => number_value(evaluate(&text, vars)?),
+        Value::Mapping(mapping) => Value::Mapping(
+            mapping
+                .into_iter()
+                .map(|(key, value)| Ok((key, substitute(value, vars)?)))
+                .collect::<BaseResult<Mapping>>()?,
+        ),
+        Value::Sequence(sequence) => Value::Sequence(
+            sequence
+                .into_iter()
+                .map(|value| substitute(value, vars))
+                .collect::<BaseResult<Vec<_>>>()?,
+        ),
+        value => value,
+    })
+}
+
+// whole numbers are kept as integers, so they're still accepted where a u64 is expected.
+fn number_value(number: f64) -> Value {
+    if number.fract() == 0.0 && number >= 0.0 && number <= u64::MAX as f64 {
+        Value::from(number as u64)
+    } else if number.fract() == 0.0 && number >= i64::MIN as f64 && number < 0.0 {
+        Value::from(number as i64)
+    } else {
+        Value::from(number)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_yaml::Value;
+
+    use super::{interpolate_env, interpolate_env_str};
+
+    // nothing sets this, so it's always unset.
+    const UNSET: &str = "IMGTOY_TEST_SURELY_UNSET";
+
+    #[test]
+    fn default_fallback() {
+        assert_eq!(
+            interpolate_env_str(&format!("${{{UNSET}:-out}}/run")).unwrap(),
+            "out/run"
+        );
+        assert_eq!(
+            interpolate_env_str(&format!("a${{{UNSET}:-}}b")).unwrap(),
+            "ab"
+        );
+    }
+
+    #[test]
+    fn set_variables_ignore_the_default() {
+        let path = std::env::var("PATH").unwrap();
+        assert_eq!(interpolate_env_str("${PATH:-fallback}").unwrap(), path);
+    }
+
+    #[test]
+    fn missing_variables() {
+        assert!(interpolate_env_str(&format!("${{{UNSET}}}"))
+            .unwrap_err()
+            .to_string()
+            .contains("is not set"));
+        assert!(interpolate_env_str("${PATH")
+            .unwrap_err()
+            .to_string()
+            .contains("unclosed"));
+    }
+
+    #[test]
+    fn whole_placeholders_keep_their_type() {
+        let value = Value::String(format!("${{{UNSET}:-12}}"));
+        assert_eq!(interpolate_env(value).unwrap(), Value::from(12));
+
+        let value = Value::String(format!("x${{{UNSET}:-12}}"));
+        assert_eq!(interpolate_env(value).unwrap(), Value::from("x12"));
+    }
+}
@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+
+use image::{Rgba, RgbaImage};
+use palette::Srgb;
+use serde_yaml::{Mapping, Value};
+
+use crate::{
+    effects::{BaseResult, Log},
+    logging::alt::Dithered,
+    source::ImageResult,
+};
+
+// PALETTE COVERAGE
+// ================
+// A palette can pass every check and still map nearly the whole image to one colour. An
+//  optional coverage check looks at the rendered output instead:
+//
+//      output:
+//        coverage:
+//          min-colours: 4
+//          threshold: 0.02
+//          on-fail: reroll
+//          retries: 5
+//
+// Every pixel of the output is matched to the nearest colour of the palette it was last
+//  dithered to (by "ordered") - giving a usage histogram. It's logged within that "ordered"'s
+//  [palette] category, alongside the colours themselves (usage #000, usage #001, ...).
+//
+// [min-colours] = how many colours must each cover at least [threshold] of the image.
+// [threshold] = the share of pixels, from 0 to 1, a colour needs to count. (0.01 by default)
+// [on-fail] = what to do with an iteration that fails:
+//      "reroll":   run the iteration again - with new random values - up to [retries] times.
+//                  if it still fails, the last attempt is kept, with a warning. (the default)
+//                  only the attempt that's kept is written to log.log - each rerolled one is
+//                  just noted in app.log.
+//      "discard":  don't save the iteration at all.
+// [retries] = how many times to reroll before giving up. (5 by default)
+//
+// For GIFs, the histogram covers every frame. Fully transparent pixels are skipped.
+
+const DEFAULT_THRESHOLD: f64 = 0.01;
+const DEFAULT_RETRIES: u64 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OnFail {
+    Reroll,
+    Discard,
+}
+
+#[derive(Debug)]
+pub struct Coverage {
+    pub min_colours: usize,
+    pub threshold: f64,
+    pub on_fail: OnFail,
+    pub retries: u64,
+}
+
+/// Reads `output.coverage`, if it's set.
+pub fn parse_coverage(output: &Mapping) -> Option<Coverage> {
+    let coverage = output.get("coverage")?;
+    let coverage = coverage
+        .as_mapping()
+        .expect("[output.coverage] must be a mapping.");
+
+    let min_colours = coverage
+        .get("min-colours")
+        .expect("[output.coverage.min-colours] must be specified.")
+        .as_u64()
+        .expect("[output.coverage.min-colours] must be a positive integer.")
+        as usize;
+
+    let threshold = coverage.get("threshold").map_or(DEFAULT_THRESHOLD, |t| {
+        t.as_f64()
+            .filter(|t| (0.0..=1.0).contains(t))
+            .expect("[output.coverage.threshold] must be a number from 0 to 1.")
+    });
+
+    let on_fail = match coverage.get("on-fail").map(Value::as_str) {
+        None | Some(Some("reroll")) => OnFail::Reroll,
+        Some(Some("discard")) => OnFail::Discard,
+        _ => panic!("[output.coverage.on-fail] must be one of reroll or discard."),
+    };
+
+    let retries = coverage.get("retries").map_or(DEFAULT_RETRIES, |r| {
+        r.as_u64()
+            .expect("[output.coverage.retries] must be a positive integer.")
+    });
+
+    Some(Coverage {
+        min_colours,
+        threshold,
+        on_fail,
+        retries,
+    })
+}
+
+impl Coverage {
+    /// Logs the usage histogram of the dithered palette within `media`, and returns whether it
+    /// passes.
+    pub fn check(
+        &self,
+        log: Log,
+        media: &ImageResult,
+        dithered: &mut Dithered,
+    ) -> BaseResult<bool> {
+        let palette = dithered.palette.clone();
+        let counts = histogram(media, &palette);
+        let total = counts.iter().sum::<u64>().max(1) as f64;
+
+        for (i, count) in counts.iter().enumerate() {
+            log.state_dithered_property(
+                dithered,
+                format!("usage #{i:03}"),
+                format!("{:.2}%", *count as f64 / total * 100.0),
+            )?;
+        }
+
+        let covering = counts
+            .iter()
+            .filter(|count| **count as f64 / total >= self.threshold)
+            .count();
+        let passed = covering >= self.min_colours;
+
+        log.state_dithered_property(
+            dithered,
+            "covering",
+            format!(
+                "{covering} of {} (needs {}, at {:.2}% or more)",
+                palette.len(),
+                self.min_colours,
+                self.threshold * 100.0
+            ),
+        )?;
+
+        Ok(passed)
+    }
+}
+
+/// Counts how many pixels are closest to each colour of the palette.
+fn histogram(media: &ImageResult, palette: &[Srgb]) -> Vec<u64> {
+    let palette = palette
+        .iter()
+        .map(|colour| {
+            let rgb: Srgb<u8> = colour.into_format();
+            [rgb.red as i32, rgb.green as i32, rgb.blue as i32]
+        })
+        .collect::<Vec<_>>();
+
+    let mut counts = vec![0; palette.len()];
+    if palette.is_empty() {
+        return counts;
+    }
+
+    // dithered output only has a handful of distinct colours, so each is only matched once.
+    let mut nearest: HashMap<[u8; 3], usize> = HashMap::new();
+
+    let mut count_image = |image: &RgbaImage| {
+        for Rgba([r, g, b, a]) in image.pixels() {
+            if *a == 0 {
+                continue;
+            }
+            let index = *nearest.entry([*r, *g, *b]).or_insert_with(|| {
+                let pixel = [*r as i32, *g as i32, *b as i32];
+                (0..palette.len())
+                    .min_by_key(|i| {
+                        palette[*i]
+                            .iter()
+                            .zip(pixel)
+                            .map(|(a, b)| (a - b).pow(2))
+                            .sum::<i32>()
+                    })
+                    .unwrap()
+            });
+            counts[index] += 1;
+        }
+    };
+
+    match media {
+        ImageResult::Image(image) => count_image(&image.to_rgba8()),
+        ImageResult::Gif(frames) => frames.iter().for_each(|frame| count_image(frame.buffer())),
+    }
+
+    counts
+}
@@ -0,0 +1,138 @@
+use std::collections::HashMap;
+
+use common_utils::colour::DeltaMetric;
+use image::{DynamicImage, Frame, RgbaImage};
+use image_effects::effect::Effect;
+use palette::{LinSrgb, Srgb};
+
+// COLOUR VISION DEFICIENCIES
+// ==========================
+// Simulates how colours appear with each of the three dichromacies, via the matrices of
+//  Machado, Oliveira & Fernandes (2009) at full severity. They're applied in linear RGB.
+//
+// As an effect, every pixel is converted - which is what the `output.simulate` previews use.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Deficiency {
+    Protanopia,
+    Deuteranopia,
+    Tritanopia,
+}
+
+impl Deficiency {
+    pub const ALL: [Deficiency; 3] = [
+        Deficiency::Protanopia,
+        Deficiency::Deuteranopia,
+        Deficiency::Tritanopia,
+    ];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "protanopia" => Some(Deficiency::Protanopia),
+            "deuteranopia" => Some(Deficiency::Deuteranopia),
+            "tritanopia" => Some(Deficiency::Tritanopia),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Deficiency::Protanopia => "protanopia",
+            Deficiency::Deuteranopia => "deuteranopia",
+            Deficiency::Tritanopia => "tritanopia",
+        }
+    }
+
+    fn matrix(&self) -> [[f32; 3]; 3] {
+        match self {
+            Deficiency::Protanopia => [
+                [0.152286, 1.052583, -0.204868],
+                [0.114503, 0.786281, 0.099216],
+                [-0.003882, -0.048116, 1.051998],
+            ],
+            Deficiency::Deuteranopia => [
+                [0.367322, 0.860646, -0.227968],
+                [0.280085, 0.672501, 0.047413],
+                [-0.011820, 0.042940, 0.968881],
+            ],
+            Deficiency::Tritanopia => [
+                [1.255528, -0.076749, -0.178779],
+                [-0.078411, 0.930809, 0.147602],
+                [0.004733, 0.691367, 0.303900],
+            ],
+        }
+    }
+
+    pub fn simulate(&self, colour: Srgb) -> Srgb {
+        let linear = colour.into_linear();
+        let rgb = [linear.red, linear.green, linear.blue];
+        let [r, g, b] = self
+            .matrix()
+            .map(|row| (row[0] * rgb[0] + row[1] * rgb[1] + row[2] * rgb[2]).clamp(0.0, 1.0));
+
+        Srgb::from_linear(LinSrgb::new(r, g, b))
+    }
+
+    /// Every pair of colours that can be told apart normally, but not with this deficiency.
+    ///
+    /// Pairs are given as `(i, j, delta)` - `delta` being their difference once simulated.
+    pub fn indistinguishable_pairs(
+        &self,
+        palette: &[Srgb],
+        threshold: f32,
+    ) -> Vec<(usize, usize, f32)> {
+        let simulated = palette
+            .iter()
+            .map(|colour| self.simulate(*colour))
+            .collect::<Vec<_>>();
+
+        let mut pairs = vec![];
+        for i in 0..palette.len() {
+            for j in (i + 1)..palette.len() {
+                if DeltaMetric::Ciede2000.delta(palette[i], palette[j]) < threshold {
+                    continue;
+                }
+
+                let delta = DeltaMetric::Ciede2000.delta(simulated[i], simulated[j]);
+                if delta < threshold {
+                    pairs.push((i, j, delta));
+                }
+            }
+        }
+
+        pairs
+    }
+
+    fn simulate_image(&self, image: &mut RgbaImage) {
+        // images tend to repeat colours a lot, so each is only converted once.
+        let mut cache: HashMap<[u8; 3], [u8; 3]> = HashMap::new();
+
+        for pixel in image.pixels_mut() {
+            let [r, g, b, a] = pixel.0;
+            let [r, g, b] = *cache.entry([r, g, b]).or_insert_with(|| {
+                let rgb: Srgb<u8> = self
+                    .simulate(Srgb::new(r, g, b).into_format())
+                    .into_format();
+                [rgb.red, rgb.green, rgb.blue]
+            });
+            pixel.0 = [r, g, b, a];
+        }
+    }
+}
+
+impl Effect<DynamicImage> for Deficiency {
+    fn affect(&self, item: DynamicImage) -> DynamicImage {
+        let mut image = item.into_rgba8();
+        self.simulate_image(&mut image);
+        DynamicImage::ImageRgba8(image)
+    }
+}
+
+impl Effect<Frame> for Deficiency {
+    fn affect(&self, item: Frame) -> Frame {
+        let (left, top, delay) = (item.left(), item.top(), item.delay());
+        let mut image = item.into_buffer();
+        self.simulate_image(&mut image);
+        Frame::from_parts(image, left, top, delay)
+    }
+}
@@ -2,5 +2,8 @@ use std::error::Error;
 
 use crate::logging::alt::SystemLog;
 
+pub mod cvd;
+pub mod quantize;
+
 pub type Log<'a> = &'a mut SystemLog;
 pub type BaseResult<T> = Result<T, Box<dyn Error>>;
@@ -0,0 +1,56 @@
+use std::collections::HashMap;
+
+use common_utils::generator::space::GenerationSpace;
+use image::{DynamicImage, Frame, RgbaImage};
+use image_effects::effect::Effect;
+use palette::{IntoColor, Lch, Srgb};
+
+/// Snaps the lightness and/or chroma of every pixel to the nearest multiple of a step, in LCH.
+///
+/// Alongside `QuantizeHue`, this acts as a perceptual posterizer.
+#[derive(Debug, Clone)]
+pub struct QuantizeLch {
+    pub lightness_step: Option<f32>,
+    pub chroma_step: Option<f32>,
+}
+
+impl QuantizeLch {
+    fn quantize(&self, image: &mut RgbaImage) {
+        // images tend to repeat colours a lot, so each is only converted once.
+        let mut cache: HashMap<[u8; 3], [u8; 3]> = HashMap::new();
+
+        for pixel in image.pixels_mut() {
+            let [r, g, b, _] = pixel.0;
+            let [r, g, b] = *cache.entry([r, g, b]).or_insert_with(|| {
+                let mut lch: Lch = Srgb::new(r, g, b).into_format::<f32>().into_color();
+                if let Some(step) = self.lightness_step {
+                    lch.l = ((lch.l / step).round() * step).clamp(0.0, 100.0);
+                }
+                if let Some(step) = self.chroma_step {
+                    lch.chroma = ((lch.chroma / step).round() * step).max(0.0);
+                }
+                // chroma can round up out of sRGB, which would then clip its lightness.
+                let rgb: Srgb<u8> = GenerationSpace::Lch.map_to_srgb(lch).into_format();
+                [rgb.red, rgb.green, rgb.blue]
+            });
+            pixel.0 = [r, g, b, pixel.0[3]];
+        }
+    }
+}
+
+impl Effect<DynamicImage> for QuantizeLch {
+    fn affect(&self, item: DynamicImage) -> DynamicImage {
+        let mut image = item.into_rgba8();
+        self.quantize(&mut image);
+        DynamicImage::ImageRgba8(image)
+    }
+}
+
+impl Effect<Frame> for QuantizeLch {
+    fn affect(&self, item: Frame) -> Frame {
+        let (left, top, delay) = (item.left(), item.top(), item.delay());
+        let mut image = item.into_buffer();
+        self.quantize(&mut image);
+        Frame::from_parts(image, left, top, delay)
+    }
+}
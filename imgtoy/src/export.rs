@@ -0,0 +1,127 @@
+use common_utils::palette::{
+    generate_palette_html_page, palette_to_gpl, palette_to_hex, palette_to_json,
+    palette_to_swatch_image,
+};
+use palette::Srgb;
+use serde_yaml::{Mapping, Value};
+
+use crate::effects::{BaseResult, Log};
+
+// PALETTE EXPORTS
+// ===============
+// Every palette used within an iteration can be written next to its output, via:
+//
+//      output:
+//        palettes: [gpl, hex, json, png, html]   # or just `all`
+//
+// Files are named after the iteration, e.g. `00003.palette.gpl`. If an iteration uses
+//  more than one palette, each after the first is numbered: `00003.palette-1.gpl`.
+//  Empty palettes are skipped, with a warning.
+//
+// The JSON is shaped as a "specified" palette, and the .gpl/.hex/.png files can be read
+//  back as a "file" palette - so a good palette can be reused directly.
+
+const SWATCH_SIZE: u32 = 32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaletteExport {
+    Gpl,
+    Hex,
+    Json,
+    Png,
+    Html,
+}
+
+impl PaletteExport {
+    const ALL: [PaletteExport; 5] = [
+        PaletteExport::Gpl,
+        PaletteExport::Hex,
+        PaletteExport::Json,
+        PaletteExport::Png,
+        PaletteExport::Html,
+    ];
+
+    fn from_name(name: &str) -> Self {
+        match name {
+            "gpl" => PaletteExport::Gpl,
+            "hex" => PaletteExport::Hex,
+            "json" => PaletteExport::Json,
+            "png" => PaletteExport::Png,
+            "html" => PaletteExport::Html,
+            _ => panic!(
+                "{name} is not a valid palette export - expected gpl, hex, json, png or html."
+            ),
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            PaletteExport::Gpl => "gpl",
+            PaletteExport::Hex => "hex",
+            PaletteExport::Json => "json",
+            PaletteExport::Png => "png",
+            PaletteExport::Html => "html",
+        }
+    }
+}
+
+/// Reads `output.palettes` - which is a format, a list of formats, or `all`.
+pub fn parse_palette_exports(output: &Mapping) -> Vec<PaletteExport> {
+    match output.get("palettes") {
+        None => vec![],
+        Some(Value::String(name)) if name == "all" => PaletteExport::ALL.to_vec(),
+        Some(Value::String(name)) => vec![PaletteExport::from_name(name)],
+        Some(Value::Sequence(names)) => names
+            .iter()
+            .map(|name| {
+                PaletteExport::from_name(
+                    name.as_str()
+                        .expect("[output.palettes] must be a list of formats."),
+                )
+            })
+            .collect(),
+        Some(_) => panic!("[output.palettes] must be a format, a list of formats, or `all`."),
+    }
+}
+
+/// Writes each palette used in iteration `i`, in every requested format.
+pub fn export_palettes(
+    log: Log,
+    out_path: &str,
+    i: u64,
+    palettes: &[Vec<Srgb>],
+    exports: &[PaletteExport],
+) -> BaseResult<()> {
+    for (n, palette) in palettes.iter().enumerate() {
+        let name = match n {
+            0 => format!("{i:<05}.palette"),
+            n => format!("{i:<05}.palette-{n}"),
+        };
+
+        if palette.is_empty() {
+            log.warn_log(
+                "export",
+                format!("[{name}] has no colours, so it wasn't exported"),
+            )?;
+            continue;
+        }
+
+        for export in exports {
+            let path = format!("{out_path}/{name}.{}", export.extension());
+
+            match export {
+                PaletteExport::Gpl => std::fs::write(&path, palette_to_gpl(&name, palette))?,
+                PaletteExport::Hex => std::fs::write(&path, palette_to_hex(palette))?,
+                PaletteExport::Json => std::fs::write(&path, palette_to_json(palette))?,
+                PaletteExport::Png => palette_to_swatch_image(palette, SWATCH_SIZE).save(&path)?,
+                PaletteExport::Html => {
+                    std::fs::write(&path, generate_palette_html_page(&name, palette))?
+                }
+            }
+
+            log.info_log("export", format!("wrote palette to [{path}]"))?;
+        }
+    }
+
+    Ok(())
+}
@@ -1,5 +1,7 @@
 use std::{error::Error, fmt::Display, fs::File, io::Write};
 
+use palette::Srgb;
+
 pub struct SystemLog {
     log: File,
     app_log: File,
@@ -7,6 +9,18 @@ pub struct SystemLog {
     indent_str: String,
     categories: Vec<String>,
     pause: bool,
+    palettes: Vec<Vec<Srgb>>,
+    held: Option<Vec<String>>,
+    dithered: Option<Dithered>,
+}
+
+/// The palette an "ordered" dithered to, and where its [palette] category ends in the log -
+/// so properties found after rendering can still be logged within it.
+pub struct Dithered {
+    pub palette: Vec<Srgb>,
+    path: String,
+    depth: usize,
+    line: Option<usize>,
 }
 
 type WriteResult<'a> = Result<&'a mut SystemLog, Box<dyn Error>>;
@@ -32,6 +46,9 @@ impl SystemLog {
             indent_str: "    ".into(),
             categories: vec![],
             pause: false,
+            palettes: vec![],
+            held: None,
+            dithered: None,
         })
     }
 
@@ -59,15 +76,43 @@ impl SystemLog {
     // effects
     pub fn header(&mut self, string: impl Display) -> WriteResult {
         self.debug_log("header", format!("{string}"))?;
-        writeln!(self.log, "[ {string:=^50} ]")?;
+        self.write_line(format!("[ {string:=^50} ]"))?;
         Ok(self)
     }
 
     pub fn message(&mut self, string: impl Display) -> WriteResult {
         self.debug_log("message", format!("{string}"))?;
         if !self.pause {
-            self.push_indent()?;
-            writeln!(self.log, "{string}")?;
+            let indent = self.indent_str.repeat(self.categories.len());
+            self.write_line(format!("{indent}{string}"))?;
+        }
+        Ok(self)
+    }
+
+    /// Holds back everything written to the log until `release` - so lines can still be
+    /// inserted into earlier categories, via `state_dithered_property`.
+    pub fn hold(&mut self) -> &mut Self {
+        self.held.get_or_insert_with(Vec::new);
+        self
+    }
+
+    /// Marks the current end of the log, to `rollback` to. Holds the log back if it isn't already.
+    pub fn checkpoint(&mut self) -> usize {
+        self.held.get_or_insert_with(Vec::new).len()
+    }
+
+    /// Forgets everything written to the log since `checkpoint`.
+    pub fn rollback(&mut self, checkpoint: usize) -> &mut Self {
+        if let Some(held) = self.held.as_mut() {
+            held.truncate(checkpoint);
+        }
+        self
+    }
+
+    /// Writes out everything held since `hold`.
+    pub fn release(&mut self) -> WriteResult {
+        for line in self.held.take().unwrap_or_default() {
+            writeln!(self.log, "{line}")?;
         }
         Ok(self)
     }
@@ -107,6 +152,11 @@ impl SystemLog {
         }
     }
 
+    /// The categories currently entered, as a dotted path.
+    pub fn path(&self) -> String {
+        self.categories.join(".")
+    }
+
     pub fn end_category(&mut self) -> WriteResult {
         self.unindent();
         let exiting_category = self.categories.pop().unwrap_or("".to_string());
@@ -114,6 +164,65 @@ impl SystemLog {
         Ok(self)
     }
 
+    // palettes
+    pub fn record_palette(&mut self, palette: &[Srgb]) -> &mut Self {
+        self.palettes.push(palette.to_vec());
+        self
+    }
+
+    /// The most recently recorded palette, if any have been recorded this iteration.
+    pub fn latest_palette(&self) -> Option<&Vec<Srgb>> {
+        self.palettes.last()
+    }
+
+    /// Returns every palette recorded since the last call.
+    pub fn take_palettes(&mut self) -> Vec<Vec<Srgb>> {
+        std::mem::take(&mut self.palettes)
+    }
+
+    /// Records the palette an "ordered" dithered to - called from within its [palette] category,
+    /// once everything else in it has been logged.
+    pub fn record_dithered(&mut self, palette: &[Srgb]) -> &mut Self {
+        self.dithered = Some(Dithered {
+            palette: palette.to_vec(),
+            path: self.path(),
+            depth: self.categories.len(),
+            line: self.held.as_ref().map(Vec::len),
+        });
+        self
+    }
+
+    /// Returns the palette last dithered to since the last call, if any.
+    pub fn take_dithered(&mut self) -> Option<Dithered> {
+        self.dithered.take()
+    }
+
+    /// Logs a property at the end of the [palette] category of `dithered` - or, if the log
+    /// wasn't held back, wherever the log currently is.
+    pub fn state_dithered_property(
+        &mut self,
+        dithered: &mut Dithered,
+        property: impl Display,
+        value: impl Display,
+    ) -> WriteResult {
+        let line = match (self.held.as_ref(), dithered.line) {
+            (Some(held), Some(line)) if line <= held.len() => line,
+            _ => return self.state_property(property, value),
+        };
+
+        self.debug_log("parsed property", format!("[{}.{property}]", dithered.path))?;
+        self.debug_log("message", format!("|{property:_>15}: {value}"))?;
+
+        if !self.pause {
+            let indent = self.indent_str.repeat(dithered.depth);
+            if let Some(held) = self.held.as_mut() {
+                held.insert(line, format!("{indent}|{property:_>15}: {value}"));
+                dithered.line = Some(line + 1);
+            }
+        }
+        Ok(self)
+    }
+
     // logs
     fn status_log(&mut self, logtype: &str, status: &str, string: impl Display) -> WriteResult {
         write!(self.app_log, "[{status:>5}] [{logtype:>20}]: ")?;
@@ -141,10 +250,18 @@ impl SystemLog {
     }
 
     // utils
-    fn push_indent(&mut self) -> WriteResult {
-        for _ in 0..self.categories.len() {
-            write!(self.log, "{}", self.indent_str)?;
+    fn write_line(&mut self, line: String) -> std::io::Result<()> {
+        match self.held.as_mut() {
+            Some(held) => held.push(line),
+            None => writeln!(self.log, "{line}")?,
         }
-        Ok(self)
+        Ok(())
+    }
+}
+
+impl Drop for SystemLog {
+    fn drop(&mut self) {
+        // anything still held - say, from an iteration that failed - is kept.
+        let _ = self.release();
     }
 }
@@ -3,35 +3,66 @@ use std::{error::Error, fs::File, path::Path};
 use image::{codecs::gif::GifEncoder, DynamicImage, Frame};
 use indicatif::{ProgressBar, ProgressStyle};
 use rand::{rngs::StdRng, SeedableRng};
-use source::{MediaType, Source, SourceKind};
+use source::{ImageResult, MediaType, Source, SourceKind};
 
-use crate::{logging::alt::SystemLog, parsers::effects::parse_effects};
+use crate::{
+    config::{format::Format, LoadedConfig},
+    coverage::OnFail,
+    logging::alt::SystemLog,
+    parsers::effects::parse_effects,
+};
 
+mod config;
+mod coverage;
 mod effects;
+mod export;
 mod logging;
 mod parsers;
+mod simulate;
 mod source;
 
 fn main() -> Result<(), Box<dyn Error>> {
-    let mut args = std::env::args();
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
 
-    if args.len() != 2 {
+    if args.first().map(String::as_str) == Some("migrate") {
+        return migrate(&args[1..]);
+    }
+
+    if args.len() != 1 {
         panic!("Expected a single arg which represents the filepath of the configuration file.");
     }
 
-    let config_file = args.nth(1).unwrap();
+    let config_file = &args[0];
 
     println!("[...] - Reading configuration file: {config_file}");
 
-    let config = std::fs::read_to_string(config_file)?;
+    println!(
+        "[...] - Parsing file as {}, resolving includes and presets...",
+        Format::from_path(config_file).extension().to_uppercase()
+    );
 
-    println!("[...] - Parsing file as YAML...");
+    let LoadedConfig {
+        config: yaml,
+        mut warnings,
+    } = config::load_config(config_file)?;
 
-    let yaml: serde_yaml::Value = serde_yaml::from_str(&config)?;
+    let library_palettes = parsers::palette::library::load_library(&yaml, &mut warnings)?;
 
-    println!("[...] - Parsing YAML as configuration");
+    if library_palettes > 0 {
+        println!("[...] - Loaded {library_palettes} palettes into the palette library");
+    }
 
-    let mut rng = StdRng::from_entropy();
+    for warning in &warnings {
+        println!("[ ! ] - {warning}");
+    }
+
+    println!("[...] - Parsing configuration");
+
+    // runs are seeded, so any of them can be reproduced by setting the logged [seed].
+    let seed = yaml.get("seed").map_or_else(rand::random, |seed| {
+        seed.as_u64().expect("[seed] must be a positive integer.")
+    });
+    let mut rng = StdRng::seed_from_u64(seed);
 
     let source = parse_source(&yaml);
 
@@ -64,9 +95,38 @@ fn main() -> Result<(), Box<dyn Error>> {
         std::fs::create_dir_all(out_path)?;
     }
 
+    // optionally, the configuration can be written back out with includes, presets and
+    // environment variables resolved - in any supported format.
+    let resolved_config = output
+        .get("resolved-config")
+        .map(|format| {
+            let format = format
+                .as_str()
+                .expect("[output.resolved-config] must be a string.");
+            Format::from_name(format)
+                .expect("[output.resolved-config] must be one of yaml, json or toml.")
+        })
+        .map(|format| format!("{out_path}/resolved-config.{}", format.extension()));
+
+    if let Some(resolved_config) = &resolved_config {
+        config::format::write_config(resolved_config, &yaml)?;
+        println!("[...] - Wrote resolved configuration to: {resolved_config}");
+    }
+
     let mut log = SystemLog::init(out_path.into())?;
-    log.header("APP INIT")?
-        .sys_log("app started")?
+    log.header("APP INIT")?.sys_log("app started")?;
+
+    for warning in &warnings {
+        log.warn_log("config", warning)?;
+    }
+
+    log.begin_category("config")?
+        .state_property("version", config::migrate::CURRENT_VERSION)?
+        .state_property("seed", seed)?
+        .state_property("warnings", warnings.len())?
+        .state_property("library", library_palettes)?
+        .state_property("resolved", resolved_config.as_deref().unwrap_or("<N/A>"))?
+        .end_category()?
         .begin_category("source")?
         .state_property("file", source_path)?
         .state_property("media-type", source_kind)?
@@ -87,12 +147,49 @@ fn main() -> Result<(), Box<dyn Error>> {
         .as_u64()
         .expect("[output.n] must be a positive integer.");
 
+    let palette_exports = export::parse_palette_exports(output);
+    let coverage = coverage::parse_coverage(output);
+    let simulations = simulate::parse_simulations(output);
+
     log.state_property("n", iterations.to_string())?;
+
+    if !palette_exports.is_empty() {
+        log.state_property(
+            "palettes",
+            palette_exports
+                .iter()
+                .map(|export| export.extension())
+                .collect::<Vec<_>>()
+                .join(", "),
+        )?;
+    }
+    if !simulations.is_empty() {
+        log.state_property(
+            "simulate",
+            simulations
+                .iter()
+                .map(|deficiency| deficiency.name())
+                .collect::<Vec<_>>()
+                .join(", "),
+        )?;
+    }
+    if let Some(coverage) = &coverage {
+        log.state_property(
+            "coverage",
+            format!(
+                "{} colours at {:.2}% or more, {:?}",
+                coverage.min_colours,
+                coverage.threshold * 100.0,
+                coverage.on_fail
+            ),
+        )?;
+    }
     log.end_category()?; // output
 
     println!("[ ! ] - Running {iterations} iterations...");
 
     let media = source.perform()?;
+    parsers::palette::extract::set_source(&media);
 
     // TODO: Add initial setup.
 
@@ -109,46 +206,81 @@ fn main() -> Result<(), Box<dyn Error>> {
     for i in 0..iterations {
         bar.inc(1);
 
-        log.begin_category(format!("[{i}]"))?;
+        // held back, so the coverage check can log within the palette it checks.
+        log.hold().begin_category(format!("[{i}]"))?;
+
+        // rerolled attempts are taken back out of the log, so only the attempt kept shows up.
+        let checkpoint = log.checkpoint();
+
+        let mut attempt = 0;
+        let (rendered, palettes, keep) = loop {
+            let yaml = config::vars::resolve_vars(&mut log, &mut rng, &yaml)?;
+            let rendered = render(&mut log, &mut rng, &yaml, &source, &media, &bar)?;
+            let palettes = log.take_palettes();
+            let dithered = log.take_dithered();
+
+            let Some(coverage) = &coverage else {
+                break (rendered, palettes, true);
+            };
+
+            let Some(mut dithered) = dithered else {
+                log.warn_log(
+                    "coverage",
+                    "nothing was dithered to a palette, so coverage can't be checked",
+                )?;
+                break (rendered, palettes, true);
+            };
+
+            if coverage.check(&mut log, &rendered, &mut dithered)? {
+                break (rendered, palettes, true);
+            }
 
-        match source.media_type {
-            MediaType::Image => {
-                let effects = parse_effects::<DynamicImage>(&mut log, &mut rng, &yaml)?;
-                let mut image = media.clone().into_image().unwrap();
-                for effect in &effects {
-                    bar.tick();
-                    image = effect.affect(image);
+            match coverage.on_fail {
+                OnFail::Discard => {
+                    log.warn_log(
+                        "coverage",
+                        format!("iteration {i} fails its coverage, discarding it"),
+                    )?;
+                    break (rendered, palettes, false);
                 }
-                image.save(format!("{out_path}/{i:<05}.png"))?;
+                OnFail::Reroll if attempt >= coverage.retries => {
+                    log.warn_log(
+                        "coverage",
+                        format!(
+                            "iteration {i} still fails its coverage after {} attempts, keeping it",
+                            attempt + 1
+                        ),
+                    )?;
+                    break (rendered, palettes, true);
+                }
+                OnFail::Reroll => {
+                    log.rollback(checkpoint);
+                    log.info_log(
+                        "coverage",
+                        format!("attempt {} fails its coverage, rerolling", attempt + 1),
+                    )?;
+                    attempt += 1;
+                }
+            }
+        };
+
+        if keep {
+            for deficiency in &simulations {
+                let name = format!("{out_path}/{i:<05}.{}", deficiency.name());
+                save(simulate::simulate(*deficiency, &rendered), &name)?;
+                log.info_log("simulate", format!("wrote {} preview", deficiency.name()))?;
             }
-            MediaType::Gif => {
-                let effects = parse_effects::<Frame>(&mut log, &mut rng, &yaml)?;
-                let frames = media.clone().into_gif().unwrap();
-                let frames_amnt = frames.len();
-                let frames = frames
-                    .into_iter()
-                    .enumerate()
-                    .map(|(i, mut frame)| {
-                        bar.set_message(format!("frame {i} of {frames_amnt}"));
-                        for effect in &effects {
-                            bar.tick();
-                            frame = effect.affect(frame);
-                        }
-                        frame
-                    })
-                    .collect::<Vec<_>>();
-
-                let file_out = File::create(format!("{out_path}/{i:<05}.gif")).unwrap();
-                let mut encoder = GifEncoder::new(file_out);
-                encoder
-                    .set_repeat(image::codecs::gif::Repeat::Infinite)
-                    .unwrap();
-                encoder.encode_frames(frames.into_iter()).unwrap();
+
+            save(rendered, &format!("{out_path}/{i:<05}"))?;
+
+            if !palette_exports.is_empty() {
+                export::export_palettes(&mut log, out_path, i, &palettes, &palette_exports)?;
             }
         }
 
         log.end_category()?;
         log.newline()?;
+        log.release()?;
     }
 
     let dur = bar.duration();
@@ -160,6 +292,104 @@ fn main() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+/// Runs the effects of a single iteration over the source media.
+fn render(
+    log: &mut SystemLog,
+    rng: &mut StdRng,
+    yaml: &serde_yaml::Value,
+    source: &Source,
+    media: &ImageResult,
+    bar: &ProgressBar,
+) -> Result<ImageResult, Box<dyn Error>> {
+    match source.media_type {
+        MediaType::Image => {
+            let effects = parse_effects::<DynamicImage>(log, rng, yaml)?;
+            let mut image = media.clone().into_image().unwrap();
+            for effect in &effects {
+                bar.tick();
+                image = effect.affect(image);
+            }
+            Ok(image.into())
+        }
+        MediaType::Gif => {
+            let effects = parse_effects::<Frame>(log, rng, yaml)?;
+            let frames = media.clone().into_gif().unwrap();
+            let frames_amnt = frames.len();
+            let frames = frames
+                .into_iter()
+                .enumerate()
+                .map(|(i, mut frame)| {
+                    bar.set_message(format!("frame {i} of {frames_amnt}"));
+                    for effect in &effects {
+                        bar.tick();
+                        frame = effect.affect(frame);
+                    }
+                    frame
+                })
+                .collect::<Vec<_>>();
+            Ok(frames.into())
+        }
+    }
+}
+
+/// Saves a result to `<name>.png`, or `<name>.gif` for gifs.
+fn save(rendered: ImageResult, name: &str) -> Result<(), Box<dyn Error>> {
+    match rendered {
+        ImageResult::Image(image) => image.save(format!("{name}.png"))?,
+        ImageResult::Gif(frames) => {
+            let file_out = File::create(format!("{name}.gif")).unwrap();
+            let mut encoder = GifEncoder::new(file_out);
+            encoder
+                .set_repeat(image::codecs::gif::Repeat::Infinite)
+                .unwrap();
+            encoder.encode_frames(frames.into_iter()).unwrap();
+        }
+    }
+    Ok(())
+}
+
+/// `imgtoy migrate <config> [output]`
+///
+/// Upgrades a single configuration file to the current version, without resolving its
+/// includes. By default the result is written next to the original as `<name>.migrated.<ext>`.
+///
+/// The output's format follows its extension - so this can also convert between formats.
+fn migrate(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let (input, output) = match args {
+        [input] => {
+            let input = Path::new(input);
+            let stem = input.file_stem().unwrap_or_default().to_string_lossy();
+            let ext = Format::from_path(input).extension();
+            (
+                input,
+                input.with_file_name(format!("{stem}.migrated.{ext}")),
+            )
+        }
+        [input, output] => (Path::new(input), Path::new(output).to_path_buf()),
+        _ => panic!("Expected [imgtoy migrate <config> [output]]."),
+    };
+
+    println!("[...] - Migrating configuration file: {}", input.display());
+
+    let config = config::format::read_config(input)?;
+
+    let mut warnings = vec![];
+    let config = config::migrate::migrate(config, &mut warnings)?;
+
+    for warning in &warnings {
+        println!("[ ! ] - {warning}");
+    }
+
+    config::format::write_config(&output, &config)?;
+
+    println!(
+        "[ ! ] - Wrote migrated configuration to: {} (note that comments aren't kept)",
+        output.display()
+    );
+
+    Ok(())
+}
+
 fn parse_source(root_value: &serde_yaml::Value) -> Source {
     let source = root_value
         .get("source")
@@ -0,0 +1,246 @@
+use image_effects::{
+    dither::{
+        error::{ErrorPropagator, WithPalette},
+        ordered::Ordered,
+    },
+    effect::Effect,
+    filter::filters::{
+        Brighten, Contrast, GradientMap, HueRotate, MultiplyHue, QuantizeHue, Saturate,
+    },
+};
+use rand::{
+    distributions::{Distribution, WeightedIndex},
+    seq::SliceRandom,
+    Rng,
+};
+use serde_yaml::Value;
+
+use crate::{
+    effects::{quantize::QuantizeLch, BaseResult, Log},
+    parsers::{
+        effects::parse_effect_list,
+        util::{parse_property_as_f64_complex, parse_property_as_u64_complex},
+    },
+};
+
+// COMBINATORS
+// ===========
+// Combinators are effect entries that contain other effects.
+// They're resolved while parsing - so every iteration makes its own choices,
+//  and the result is flattened into the regular list of effects.
+//
+// "one-of":
+//      picks ONE of the listed sub-pipelines.
+//      each option is either a list of effects, or a mapping with [effects] and an
+//          optional [weight] (defaults to 1.0).
+//
+// "shuffle":
+//      applies the listed effects in a random order.
+//      to keep a few effects together, wrap them in a "group".
+//
+// "repeat":
+//      applies the sub-pipeline [times] times.
+//      the sub-pipeline is re-parsed for every repetition, so random parameters
+//          are rolled again each time.
+//
+// "group":
+//      nests a sub-pipeline, applied as a whole with a shared [chance] (defaults to 1.0).
+
+pub fn parse_one_of<T>(
+    log: Log,
+    rng: &mut impl Rng,
+    value: &Value,
+) -> BaseResult<Vec<Box<dyn Effect<T>>>>
+where
+    HueRotate: Effect<T>,
+    Contrast: Effect<T>,
+    Brighten: Effect<T>,
+    Saturate: Effect<T>,
+    GradientMap: Effect<T>,
+    QuantizeHue: Effect<T>,
+    QuantizeLch: Effect<T>,
+    MultiplyHue: Effect<T>,
+    Ordered: Effect<T>,
+    ErrorPropagator<'static, 'static, WithPalette>: Effect<T>,
+{
+    log.begin_category("one-of")?;
+
+    let options = value
+        .get("one-of")
+        .unwrap()
+        .as_sequence()
+        .expect("[one-of] must be a list of options.");
+
+    if options.is_empty() {
+        panic!("[one-of] must have at least one option.");
+    }
+
+    let options = options
+        .iter()
+        .enumerate()
+        .map(|(i, option)| {
+            if let Some(effects) = option.as_sequence() {
+                (1.0, effects)
+            } else if option.is_mapping() {
+                let weight = option.get("weight").map_or(1.0, |weight| {
+                    weight
+                        .as_f64()
+                        .unwrap_or_else(|| panic!("[one-of.{i}.weight] must be a float."))
+                });
+                let effects = option
+                    .get("effects")
+                    .unwrap_or_else(|| panic!("[one-of.{i}] requires [effects]."))
+                    .as_sequence()
+                    .unwrap_or_else(|| panic!("[one-of.{i}.effects] must be a list."));
+                (weight, effects)
+            } else {
+                panic!("[one-of.{i}] must be a list of effects, or a mapping with [effects].");
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let weights = WeightedIndex::new(options.iter().map(|(weight, _)| *weight))
+        .expect("[one-of] weights must be non-negative, and at least one must be positive.");
+    let picked = weights.sample(rng);
+
+    log.state_property("options", options.len())?;
+    log.state_property(
+        "picked",
+        format!("#{picked:03} (weight {})", options[picked].0),
+    )?;
+
+    let effects = parse_effect_list::<T>(log, rng, options[picked].1)?;
+
+    log.end_category()?;
+
+    Ok(effects)
+}
+
+pub fn parse_shuffle<T>(
+    log: Log,
+    rng: &mut impl Rng,
+    value: &Value,
+) -> BaseResult<Vec<Box<dyn Effect<T>>>>
+where
+    HueRotate: Effect<T>,
+    Contrast: Effect<T>,
+    Brighten: Effect<T>,
+    Saturate: Effect<T>,
+    GradientMap: Effect<T>,
+    QuantizeHue: Effect<T>,
+    QuantizeLch: Effect<T>,
+    MultiplyHue: Effect<T>,
+    Ordered: Effect<T>,
+    ErrorPropagator<'static, 'static, WithPalette>: Effect<T>,
+{
+    log.begin_category("shuffle")?;
+
+    let entries = value
+        .get("shuffle")
+        .unwrap()
+        .as_sequence()
+        .expect("[shuffle] must be a list of effects.");
+
+    let mut order = (0..entries.len()).collect::<Vec<_>>();
+    order.shuffle(rng);
+
+    log.state_property("order", format!("{order:?}"))?;
+
+    let shuffled = order
+        .iter()
+        .map(|i| entries[*i].clone())
+        .collect::<Vec<_>>();
+
+    let effects = parse_effect_list::<T>(log, rng, &shuffled)?;
+
+    log.end_category()?;
+
+    Ok(effects)
+}
+
+pub fn parse_repeat<T>(
+    log: Log,
+    rng: &mut impl Rng,
+    value: &Value,
+) -> BaseResult<Vec<Box<dyn Effect<T>>>>
+where
+    HueRotate: Effect<T>,
+    Contrast: Effect<T>,
+    Brighten: Effect<T>,
+    Saturate: Effect<T>,
+    GradientMap: Effect<T>,
+    QuantizeHue: Effect<T>,
+    QuantizeLch: Effect<T>,
+    MultiplyHue: Effect<T>,
+    Ordered: Effect<T>,
+    ErrorPropagator<'static, 'static, WithPalette>: Effect<T>,
+{
+    log.begin_category("repeat")?;
+
+    let param = value.get("repeat").unwrap();
+
+    let times = parse_property_as_u64_complex(log, rng, param, "times")?
+        .expect("[repeat] requires [times].");
+
+    let entries = param
+        .get("effects")
+        .expect("[repeat] requires [effects].")
+        .as_sequence()
+        .expect("[repeat.effects] must be a list of effects.");
+
+    let mut effects = Vec::new();
+
+    for i in 0..times {
+        log.begin_category(format!("[{i}]"))?;
+        effects.extend(parse_effect_list::<T>(log, rng, entries)?);
+        log.end_category()?;
+    }
+
+    log.end_category()?;
+
+    Ok(effects)
+}
+
+pub fn parse_group<T>(
+    log: Log,
+    rng: &mut impl Rng,
+    value: &Value,
+) -> BaseResult<Vec<Box<dyn Effect<T>>>>
+where
+    HueRotate: Effect<T>,
+    Contrast: Effect<T>,
+    Brighten: Effect<T>,
+    Saturate: Effect<T>,
+    GradientMap: Effect<T>,
+    QuantizeHue: Effect<T>,
+    QuantizeLch: Effect<T>,
+    MultiplyHue: Effect<T>,
+    Ordered: Effect<T>,
+    ErrorPropagator<'static, 'static, WithPalette>: Effect<T>,
+{
+    log.begin_category("group")?;
+
+    let param = value.get("group").unwrap();
+
+    let chance = parse_property_as_f64_complex(log, rng, param, "chance")?.unwrap_or(1.0);
+
+    let entries = param
+        .get("effects")
+        .expect("[group] requires [effects].")
+        .as_sequence()
+        .expect("[group.effects] must be a list of effects.");
+
+    let applied = rng.gen_range(0.0..1.0) < chance;
+
+    log.state_property("applied", applied)?;
+
+    let effects = if applied {
+        parse_effect_list::<T>(log, rng, entries)?
+    } else {
+        vec![]
+    };
+
+    log.end_category()?;
+
+    Ok(effects)
+}
@@ -1,98 +1,262 @@
 use image_effects::filter::filters::GradientMap;
-use palette::{IntoColor, Srgb};
-use rand::Rng;
+use palette::{IntoColor, Lab, Lch, Mix, Oklab, Srgb};
+use rand::{seq::SliceRandom, Rng};
 use serde_yaml::Value;
 
 use crate::{
     effects::{BaseResult, Log},
     parsers::{
-        palette::gen_with_lightness,
-        util::{parse_property_as_f64_complex, parse_property_as_u64_complex},
+        palette::{
+            build_palette, gen_with_lightness, harmony_offsets, parse_colour, parse_seed_hue,
+            GenerationSpace,
+        },
+        util::{
+            logless::parse_f64_complex, parse_property_as_f64_complex, parse_property_as_str,
+            parse_property_as_u64_complex,
+        },
     },
 };
 
 pub fn parse_gradient_map(log: Log, rng: &mut impl Rng, value: &Value) -> BaseResult<GradientMap> {
+    let param = value.get("gradient-map").unwrap();
+
+    // the category's closed on every path - errors included.
     log.begin_category("gradient-map")?;
+    let effect = build_gradient_map(log, rng, param);
+    log.end_category()?;
 
-    let param = value.get("gradient-map").unwrap();
+    effect
+}
 
-    let effect = Ok(if param.is_mapping() {
-        let amnt = parse_property_as_u64_complex(log, rng, param, "amnt")?
-            .expect("[gradient-map] as a mapping needs an [amnt] property.");
-        let noise = parse_property_as_f64_complex(log, rng, param, "noise")?.unwrap_or(0.0);
-        let noise_chance =
-            parse_property_as_f64_complex(log, rng, param, "noise-chance")?.unwrap_or(1.0);
-        let min_brightness =
-            parse_property_as_f64_complex(log, rng, param, "min-brightness")?.unwrap_or(0.0);
-        let max_brightness =
-            parse_property_as_f64_complex(log, rng, param, "max-brightness")?.unwrap_or(100.0);
-
-        let generated_map = generate_gradient_map(
-            rng,
-            amnt,
-            noise,
-            noise_chance,
-            min_brightness,
-            max_brightness,
-        );
-
-        GradientMap::with_map(generated_map)
-    } else {
-        log.alert("too tired. don't wanna do gradient map right now. sry.");
+fn build_gradient_map(log: Log, rng: &mut impl Rng, param: &Value) -> BaseResult<GradientMap> {
+    Ok(
+        if let Some(stops) = param.get("stops").or(param.as_sequence().map(|_| param)) {
+            let interpolation = match param.get("interpolate") {
+                None => Interpolation::Lch,
+                Some(interpolation) => Interpolation::from_name(
+                    interpolation
+                        .as_str()
+                        .expect("[gradient-map.interpolate] must be a string."),
+                ),
+            };
+            log.state_property("interpolate", format!("{interpolation:?}").to_lowercase())?;
 
-        let map = param
-            .as_sequence()
-            .expect("[gradient-map] must be a list of valid mappings (or a mapping itself).");
-        let map = map
-            .iter()
-            .map(|entry| {
-                let entry = entry
-                    .as_mapping()
-                    .expect("entries in [gradient-map] must be mappings.");
-                let luma = entry
-                    .get("luma")
-                    .expect("[gradient-map.?.luma] is required.")
-                    .as_f64()
-                    .expect("[gradient-map.?.luma] must be a valid positive float.")
-                    as f32;
-
-                let col = entry
-                    .get("colour")
-                    .expect("[gradient-map.?.colour] is required.")
-                    .as_mapping()
-                    .expect("[gradient-map.?.colour] must be a mapping.");
-
-                // (*parse_colour(log, rng, col)?.choose(rng).unwrap(), luma)
-                panic!("seems like you tried to make a gradient map! whoops! no way! we're doing a refactor and this thing is FUCKED!");
-            })
-            .collect::<Vec<_>>();
+            let stops = parse_gradient_stops(log, rng, stops)?;
+            log.state_property("stops", stops.len())?;
 
-        GradientMap::with_map(map)
-    });
+            GradientMap::with_map(interpolate_stops(&stops, interpolation))
+        } else {
+            let strategy = parse_map_strategy(log, rng, param)?;
+            let amnt = match &strategy {
+                MapStrategy::FromPalette { palette, .. } => palette.len() as u64,
+                _ => parse_property_as_u64_complex(log, rng, param, "amnt")?
+                    .expect("[gradient-map] as a mapping needs an [amnt] property, or [stops]."),
+            };
+            if amnt == 0 {
+                return Err("[gradient-map] needs at least one colour.".into());
+            }
+            let noise = parse_property_as_f64_complex(log, rng, param, "noise")?.unwrap_or(0.0);
+            let noise_chance =
+                parse_property_as_f64_complex(log, rng, param, "noise-chance")?.unwrap_or(1.0);
+            let min_brightness =
+                parse_property_as_f64_complex(log, rng, param, "min-brightness")?.unwrap_or(0.0);
+            let max_brightness =
+                parse_property_as_f64_complex(log, rng, param, "max-brightness")?.unwrap_or(100.0);
 
-    log.end_category()?;
+            let generated_map = generate_gradient_map(
+                rng,
+                &strategy,
+                amnt,
+                noise,
+                noise_chance,
+                min_brightness,
+                max_brightness,
+            );
 
-    effect
+            match param.get("interpolate") {
+                Some(interpolation) => {
+                    let interpolation = Interpolation::from_name(
+                        interpolation
+                            .as_str()
+                            .expect("[gradient-map.interpolate] must be a string."),
+                    );
+                    log.state_property("interpolate", format!("{interpolation:?}").to_lowercase())?;
+                    GradientMap::with_map(interpolate_stops(&generated_map, interpolation))
+                }
+                None => GradientMap::with_map(generated_map),
+            }
+        },
+    )
+}
+
+// GENERATED MAPS
+// ==============
+// A gradient map given as a mapping (without [stops]) is generated - [amnt] stops, spread
+//  evenly from [min-brightness] to [max-brightness]. How they're coloured is up to [strategy]:
+//
+// "random" (default):  every stop gets a random hue and chroma.
+// "mono":              every stop shares one hue - only lightness varies.
+// "duotone":           shadows take one hue, highlights another ([second-hue], opposite the
+//                      first by default), blending in between.
+// "sweep":             the hue rotates by [sweep] degrees (120 by default) from dark to light.
+// "harmony":           the hues of a named [harmony] (check HUE STRATEGY - [angle] works the
+//                      same way) each take an equal band of lightness, from dark to light.
+// "from-palette":      a [palette] (any palette) is sorted by lightness, and used as the map.
+//                      [spacing] is either even (default), or lightness - where each colour
+//                      sits at its own lightness.
+//
+// The first hue is [seed-hue] - the same as random_v2. Stops share one [chroma], random
+//  from 20 to 80 by default, which is reduced as needed to stay within sRGB.
+// [noise] and [noise-chance] then nudge each stop's lightness, and [interpolate] can be given
+//  to blend the stops, same as an authored map.
+
+pub enum MapStrategy {
+    Random,
+    Mono { hue: f64, chroma: f64 },
+    Duotone { hues: (f64, f64), chroma: f64 },
+    Sweep { hue: f64, sweep: f64, chroma: f64 },
+    Harmony { hues: Vec<f64>, chroma: f64 },
+    FromPalette { palette: Vec<Srgb>, even: bool },
+}
+
+fn parse_map_strategy(log: Log, rng: &mut impl Rng, param: &Value) -> BaseResult<MapStrategy> {
+    let strategy = parse_property_as_str(log, param, "strategy")?.unwrap_or("random".into());
+
+    if strategy == "random" {
+        return Ok(MapStrategy::Random);
+    }
+
+    if strategy == "from-palette" {
+        let palette = param
+            .get("palette")
+            .expect("if [gradient-map.strategy] is [from-palette], [palette] is required.");
+        let even = match parse_property_as_str(log, param, "spacing")?.as_deref() {
+            None | Some("even") => true,
+            Some("lightness") => false,
+            Some(spacing) => {
+                panic!("{spacing} is not a valid [spacing] - expected even or lightness.")
+            }
+        };
+
+        return Ok(MapStrategy::FromPalette {
+            palette: build_palette(log, rng, palette)?,
+            even,
+        });
+    }
+
+    let hue = parse_seed_hue(log, rng, param)?;
+    let chroma = match param.get("chroma") {
+        Some(chroma) => parse_f64_complex(rng, chroma),
+        None => rng.gen_range(20.0..80.0),
+    };
+    log.state_property("chroma", chroma)?;
+
+    Ok(match strategy.as_str() {
+        "mono" => MapStrategy::Mono { hue, chroma },
+        "duotone" => {
+            let second = match param.get("second-hue") {
+                Some(second) => parse_f64_complex(rng, second),
+                None => hue + 180.0,
+            };
+            log.state_property("second-hue", second)?;
+            MapStrategy::Duotone {
+                hues: (hue, second),
+                chroma,
+            }
+        }
+        "sweep" => MapStrategy::Sweep {
+            hue,
+            sweep: parse_property_as_f64_complex(log, rng, param, "sweep")?.unwrap_or(120.0),
+            chroma,
+        },
+        "harmony" => {
+            let harmony = parse_property_as_str(log, param, "harmony")?
+                .expect("if [gradient-map.strategy] is [harmony], [harmony] is required.");
+            let angle = parse_property_as_f64_complex(log, rng, param, "angle")?.unwrap_or(30.0);
+            let offsets = harmony_offsets(&harmony, angle)
+                .unwrap_or_else(|| panic!("{harmony} is not a valid harmony."));
+
+            MapStrategy::Harmony {
+                hues: [vec![hue], offsets.iter().map(|offset| hue + offset).collect()].concat(),
+                chroma,
+            }
+        }
+        _ => panic!(
+            "{strategy} is not a valid [gradient-map.strategy] - expected random, mono, duotone, sweep, harmony or from-palette."
+        ),
+    })
 }
 
 fn generate_gradient_map(
     rng: &mut impl Rng,
+    strategy: &MapStrategy,
     amnt: u64,
     noise: f64,
     noise_chance: f64,
     min_brightness: f64,
     max_brightness: f64,
 ) -> Vec<(Srgb, f32)> {
-    let step_size = (max_brightness - min_brightness) / ((amnt - 1) as f64);
+    let step_size = match amnt {
+        1 => 0.0,
+        _ => (max_brightness - min_brightness) / ((amnt - 1) as f64),
+    };
 
     // must always start from the minimum brightness.
     let mut step_loc = min_brightness as f32;
 
     let mut palette = Vec::new();
 
-    for _ in 0..amnt {
-        palette.push((gen_with_lightness(rng, step_loc), step_loc / 100.0));
-        step_loc += step_size as f32;
+    if let MapStrategy::FromPalette {
+        palette: colours,
+        even,
+    } = strategy
+    {
+        let mut colours = colours
+            .iter()
+            .map(|colour| IntoColor::<Lch>::into_color(*colour))
+            .collect::<Vec<_>>();
+        colours.sort_by(|a, b| a.l.total_cmp(&b.l));
+
+        for (i, colour) in colours.into_iter().enumerate() {
+            let luma = match (even, amnt) {
+                (false, _) => colour.l / 100.0,
+                (true, 1) => 0.0,
+                (true, _) => i as f32 / (amnt - 1) as f32,
+            };
+            palette.push((colour, luma));
+        }
+    } else {
+        for i in 0..amnt {
+            // how far along the map this stop is, from 0 to 1.
+            let t = match amnt {
+                1 => 0.0,
+                _ => i as f64 / (amnt - 1) as f64,
+            };
+
+            let colour = match strategy {
+                MapStrategy::Mono { hue, chroma } => {
+                    Lch::new(step_loc, *chroma as f32, *hue as f32)
+                }
+                MapStrategy::Duotone { hues, chroma } => {
+                    let shadow: Lab =
+                        Lch::new(step_loc, *chroma as f32, hues.0 as f32).into_color();
+                    let highlight: Lab =
+                        Lch::new(step_loc, *chroma as f32, hues.1 as f32).into_color();
+                    shadow.mix(highlight, t as f32).into_color()
+                }
+                MapStrategy::Sweep { hue, sweep, chroma } => {
+                    Lch::new(step_loc, *chroma as f32, (hue + sweep * t) as f32)
+                }
+                MapStrategy::Harmony { hues, chroma } => {
+                    let band = ((t * hues.len() as f64) as usize).min(hues.len() - 1);
+                    Lch::new(step_loc, *chroma as f32, hues[band] as f32)
+                }
+                _ => gen_with_lightness(rng, step_loc),
+            };
+
+            palette.push((colour, step_loc / 100.0));
+            step_loc += step_size as f32;
+        }
     }
 
     if noise != 0.0 && noise_chance != 0.0 {
@@ -106,6 +270,140 @@ fn generate_gradient_map(
 
     palette
         .into_iter()
-        .map(|(color, step)| (color.into_color(), step))
+        .map(|(color, step)| (GenerationSpace::Lch.map_to_srgb(color), step))
         .collect::<Vec<_>>()
 }
+
+// GRADIENT STOPS
+// ==============
+// A gradient map can be authored as a list of stops - either directly, or as [stops] in a
+//  mapping alongside [interpolate]:
+//
+//      gradient-map:
+//        interpolate: oklab    # lch (default), oklab, rgb or step
+//        stops:
+//          - { luma: 0.0, colour: "#1a1c2c" }
+//          - { luma: 1.0, colour: { rgb: F4F4F4 } }
+//
+// [luma] is from 0 to 1, and [colour] can be any colour - if it produces several (like
+//  [shades] or [random]), one is picked at random.
+//
+// Stops are blended in the chosen space, and resampled into many small steps - so the result
+//  doesn't depend on how the effect itself blends. "step" doesn't blend at all; each stop's
+//  colour holds until the next one.
+
+/// How many stops a blended gradient is resampled into.
+const RESAMPLED_STOPS: usize = 128;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interpolation {
+    Lch,
+    Oklab,
+    Rgb,
+    Step,
+}
+
+impl Interpolation {
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "lch" => Interpolation::Lch,
+            "oklab" => Interpolation::Oklab,
+            "rgb" => Interpolation::Rgb,
+            "step" => Interpolation::Step,
+            _ => panic!("{name} is not a valid interpolation - expected lch, oklab, rgb or step."),
+        }
+    }
+
+    pub fn mix(&self, a: Srgb, b: Srgb, t: f32) -> Srgb {
+        match self {
+            Interpolation::Lch => {
+                let (a, b): (Lch, Lch) = (a.into_color(), b.into_color());
+                GenerationSpace::Lch.map_to_srgb(a.mix(b, t))
+            }
+            Interpolation::Oklab => {
+                let (a, b): (Oklab, Oklab) = (a.into_color(), b.into_color());
+                a.mix(b, t).into_color()
+            }
+            Interpolation::Rgb => a.mix(b, t),
+            Interpolation::Step => a,
+        }
+    }
+}
+
+/// Reads a list of `{ luma, colour }` stops, sorted by luma.
+pub fn parse_gradient_stops(
+    log: Log,
+    rng: &mut impl Rng,
+    stops: &Value,
+) -> BaseResult<Vec<(Srgb, f32)>> {
+    let stops = stops
+        .as_sequence()
+        .expect("[gradient-map.stops] must be a list of { luma, colour } mappings.");
+
+    if stops.is_empty() {
+        return Err("[gradient-map] needs at least one stop.".into());
+    }
+
+    let mut stops = stops
+        .iter()
+        .enumerate()
+        .map(|(i, stop)| {
+            let luma = parse_property_as_f64_complex(log, rng, stop, "luma")?
+                .unwrap_or_else(|| panic!("[gradient-map.{i}.luma] is required."));
+            if !(0.0..=1.0).contains(&luma) {
+                return Err(format!("[gradient-map.{i}.luma] must be between 0 and 1.").into());
+            }
+
+            let colour = stop
+                .get("colour")
+                .unwrap_or_else(|| panic!("[gradient-map.{i}.colour] is required."));
+            let colour = *parse_colour(log, rng, colour)
+                .map_err(|err| format!("[gradient-map.{i}.colour] {err}"))?
+                .choose(rng)
+                .unwrap_or_else(|| panic!("[gradient-map.{i}.colour] produced no colours."));
+
+            Ok((colour, luma as f32))
+        })
+        .collect::<BaseResult<Vec<_>>>()?;
+
+    stops.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+    Ok(stops)
+}
+
+/// Turns authored stops into the stops given to the effect, blended in the chosen space.
+pub fn interpolate_stops(stops: &[(Srgb, f32)], interpolation: Interpolation) -> Vec<(Srgb, f32)> {
+    if stops.len() < 2 {
+        return stops.to_vec();
+    }
+
+    if interpolation == Interpolation::Step {
+        // each colour holds until just before the next stop.
+        let mut stepped = vec![stops[0]];
+        for pair in stops.windows(2) {
+            let ((a, _), (b, luma)) = (pair[0], pair[1]);
+            stepped.push((a, (luma - f32::EPSILON).max(0.0)));
+            stepped.push((b, luma));
+        }
+        return stepped;
+    }
+
+    let (first, last) = (stops[0].1, stops[stops.len() - 1].1);
+    (0..RESAMPLED_STOPS)
+        .map(|i| {
+            let luma = first + (last - first) * i as f32 / (RESAMPLED_STOPS - 1) as f32;
+            let next = stops
+                .iter()
+                .position(|(_, stop)| *stop >= luma)
+                .unwrap_or(stops.len() - 1)
+                .max(1);
+            let ((a, from), (b, to)) = (stops[next - 1], stops[next]);
+            let t = if to > from {
+                ((luma - from) / (to - from)).clamp(0.0, 1.0)
+            } else {
+                1.0
+            };
+            (interpolation.mix(a, b, t), luma)
+        })
+        .collect()
+}
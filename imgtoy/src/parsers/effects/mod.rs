@@ -12,15 +12,20 @@ use rand::Rng;
 use serde_yaml::Value;
 
 use crate::{
-    effects::{BaseResult, Log},
+    effects::{quantize::QuantizeLch, BaseResult, Log},
     parsers::{
-        effects::{gradient_map::parse_gradient_map, quantize_hue::parse_quantize_hue},
+        effects::{
+            combinators::{parse_group, parse_one_of, parse_repeat, parse_shuffle},
+            gradient_map::parse_gradient_map,
+            quantize_hue::parse_quantize_hue,
+        },
         error_propagator::parse_error_propagator,
         ordered::parse_ordered,
         properties::parse_factor,
     },
 };
 
+pub mod combinators;
 pub mod gradient_map;
 pub mod quantize_hue;
 
@@ -36,6 +41,11 @@ pub enum EffectKind {
 
     Ordered,
     ErrorPropagator,
+
+    OneOf,
+    Shuffle,
+    Repeat,
+    Group,
 }
 
 impl From<&str> for EffectKind {
@@ -49,6 +59,10 @@ impl From<&str> for EffectKind {
             "quantize-hue" => Self::QuantizeHue,
             "multiply-hue" => Self::MultiplyHue,
             "ordered" => Self::Ordered,
+            "one-of" => Self::OneOf,
+            "shuffle" => Self::Shuffle,
+            "repeat" => Self::Repeat,
+            "group" => Self::Group,
             _ => Self::ErrorPropagator,
         }
     }
@@ -79,6 +93,7 @@ where
     Saturate: Effect<T>,
     GradientMap: Effect<T>,
     QuantizeHue: Effect<T>,
+    QuantizeLch: Effect<T>,
     MultiplyHue: Effect<T>,
     Ordered: Effect<T>,
     ErrorPropagator<'static, 'static, WithPalette>: Effect<T>,
@@ -89,6 +104,30 @@ where
         .as_sequence()
         .expect("[effects] must be a list - wasn't.");
 
+    parse_effect_list::<T>(log, rng, effects)
+}
+
+/// Parses a list of effect entries into a flat pipeline.
+///
+/// Combinator entries (`one-of`, `shuffle`, `repeat`, `group`) are expanded in place,
+/// so the result is always a plain list of effects to apply in order.
+pub fn parse_effect_list<T>(
+    log: Log,
+    rng: &mut impl Rng,
+    effects: &[Value],
+) -> BaseResult<Vec<Box<dyn Effect<T>>>>
+where
+    HueRotate: Effect<T>,
+    Contrast: Effect<T>,
+    Brighten: Effect<T>,
+    Saturate: Effect<T>,
+    GradientMap: Effect<T>,
+    QuantizeHue: Effect<T>,
+    QuantizeLch: Effect<T>,
+    MultiplyHue: Effect<T>,
+    Ordered: Effect<T>,
+    ErrorPropagator<'static, 'static, WithPalette>: Effect<T>,
+{
     Ok(effects
         .iter()
         .enumerate()
@@ -97,7 +136,7 @@ where
                 .as_mapping()
                 .unwrap_or_else(|| panic!("[effects.{i}] must be a map - wasn't."))
         })
-        .map(|effect| {
+        .flat_map(|effect| {
             let keys = effect.keys().len();
             if keys != 1 {
                 panic!("only one key [the effect name] is accepted by effect - found {keys}.");
@@ -108,7 +147,11 @@ where
         .collect::<Vec<_>>())
 }
 
-fn parse_effect<T>(log: Log, rng: &mut impl Rng, effect: &Value) -> BaseResult<Box<dyn Effect<T>>>
+fn parse_effect<T>(
+    log: Log,
+    rng: &mut impl Rng,
+    effect: &Value,
+) -> BaseResult<Vec<Box<dyn Effect<T>>>>
 where
     HueRotate: Effect<T>,
     Contrast: Effect<T>,
@@ -116,20 +159,21 @@ where
     Saturate: Effect<T>,
     GradientMap: Effect<T>,
     QuantizeHue: Effect<T>,
+    QuantizeLch: Effect<T>,
     MultiplyHue: Effect<T>,
     Ordered: Effect<T>,
     ErrorPropagator<'static, 'static, WithPalette>: Effect<T>,
 {
     let kind = parse_effect_kind(effect);
 
-    Ok(match kind {
+    let effect: Box<dyn Effect<T>> = match kind {
         EffectKind::HueRotate => Box::new(parse_hue_rotate(log, rng, effect)?),
         EffectKind::Contrast => Box::new(parse_contrast(log, rng, effect)?),
         EffectKind::Brighten => Box::new(parse_brighten(log, rng, effect)?),
         EffectKind::Saturate => Box::new(parse_saturate(log, rng, effect)?),
         EffectKind::MultiplyHue => Box::new(parse_multiply_hue(log, rng, effect)?),
         EffectKind::GradientMap => Box::new(parse_gradient_map(log, rng, effect)?),
-        EffectKind::QuantizeHue => Box::new(parse_quantize_hue(log, rng, effect)?),
+        EffectKind::QuantizeHue => return parse_quantize_hue(log, rng, effect),
         EffectKind::Ordered => {
             log.begin_category("ordered")?;
             let fx = parse_ordered(log, rng, effect)?;
@@ -155,7 +199,13 @@ where
             log.end_category()?;
             Box::new(fx)
         }
-    })
+        EffectKind::OneOf => return parse_one_of(log, rng, effect),
+        EffectKind::Shuffle => return parse_shuffle(log, rng, effect),
+        EffectKind::Repeat => return parse_repeat(log, rng, effect),
+        EffectKind::Group => return parse_group(log, rng, effect),
+    };
+
+    Ok(vec![effect])
 }
 
 pub fn parse_hue_rotate(log: Log, rng: &mut impl Rng, value: &Value) -> BaseResult<HueRotate> {
@@ -1,19 +1,77 @@
-use image_effects::filter::filters::QuantizeHue;
+use image_effects::{effect::Effect, filter::filters::QuantizeHue};
+use palette::{IntoColor, Lch};
 use rand::Rng;
 use serde_yaml::Value;
 
 use crate::{
-    effects::{BaseResult, Log},
-    parsers::util::parse_value_as_f64_sequence_complex,
+    effects::{quantize::QuantizeLch, BaseResult, Log},
+    parsers::{
+        palette::{build_palette, harmony_offsets, parse_seed_hue},
+        util::{
+            parse_property_as_f64_complex, parse_property_as_str, parse_property_as_u64_complex,
+            parse_value_as_f64_sequence_complex,
+        },
+    },
 };
 
-pub fn parse_quantize_hue(log: Log, rng: &mut impl Rng, value: &Value) -> BaseResult<QuantizeHue> {
-    log.begin_category("quantize-hue")?;
+// QUANTIZE HUE
+// ============
+// Snaps every hue in the image to the nearest of a set of hues. The hues are either listed
+//  directly, or generated:
+//
+//      quantize-hue: { hues: [0.0, 120.0, { min: 200.0, max: 260.0 }] }
+//      quantize-hue: { evenly: 6, offset: { min: 0, max: 60 } }
+//      quantize-hue: { harmony: triadic, seed-hue: { rgb: FF6600 } }
+//      quantize-hue: { from-palette: latest }
+//
+// "evenly":        N hues, spread evenly around the wheel - starting from [offset] (0 by default).
+// "harmony":       the seed hue, and the hues of a named harmony from it. (check HUE STRATEGY -
+//                  [seed-hue] and [angle] work the same way)
+// "from-palette":  the hues of a palette - either any palette, or `latest` for the palette
+//                  most recently used in this iteration (like one from a previous "ordered").
+//                  greys are skipped, as they don't have a meaningful hue.
+//
+// [lightness-step] and [chroma-step] can also be given, to snap lightness and chroma to the
+//  nearest multiple of the step too - turning this into a perceptual posterizer:
+//
+//      quantize-hue: { evenly: 8, lightness-step: 20, chroma-step: 25 }
+
+/// Colours with less chroma than this are skipped when taking hues from a palette.
+const GREY_CHROMA: f32 = 5.0;
 
+pub fn parse_quantize_hue<T>(
+    log: Log,
+    rng: &mut impl Rng,
+    value: &Value,
+) -> BaseResult<Vec<Box<dyn Effect<T>>>>
+where
+    QuantizeHue: Effect<T>,
+    QuantizeLch: Effect<T>,
+{
     let param = value.get("quantize-hue").unwrap();
 
-    let hues = parse_value_as_f64_sequence_complex(log, rng, param, "hues")?
-        .expect("[quantize-hue.hues] must be a list of hues/hue options.");
+    // the category's closed on every path - errors included.
+    log.begin_category("quantize-hue")?;
+    let effects = build_quantize_hue(log, rng, param);
+    log.end_category()?;
+
+    effects
+}
+
+fn build_quantize_hue<T>(
+    log: Log,
+    rng: &mut impl Rng,
+    param: &Value,
+) -> BaseResult<Vec<Box<dyn Effect<T>>>>
+where
+    QuantizeHue: Effect<T>,
+    QuantizeLch: Effect<T>,
+{
+    let hues = parse_hues(log, rng, param)?;
+
+    if hues.is_empty() {
+        return Err("[quantize-hue] needs at least one hue.".into());
+    }
 
     let hues = hues.iter().map(|h| *h as f32).collect::<Vec<_>>();
 
@@ -25,7 +83,74 @@ pub fn parse_quantize_hue(log: Log, rng: &mut impl Rng, value: &Value) -> BaseRe
 
     log.state_property("hues", format!("{hues_str:?}").as_str())?;
 
-    log.end_category()?;
+    let lightness_step = parse_property_as_f64_complex(log, rng, param, "lightness-step")?;
+    let chroma_step = parse_property_as_f64_complex(log, rng, param, "chroma-step")?;
+
+    if lightness_step.is_some_and(|step| step <= 0.0) || chroma_step.is_some_and(|step| step <= 0.0)
+    {
+        return Err("[quantize-hue] steps must be above 0.".into());
+    }
+
+    let mut effects: Vec<Box<dyn Effect<T>>> = vec![Box::new(QuantizeHue::with_hues(hues))];
+
+    if lightness_step.is_some() || chroma_step.is_some() {
+        effects.push(Box::new(QuantizeLch {
+            lightness_step: lightness_step.map(|step| step as f32),
+            chroma_step: chroma_step.map(|step| step as f32),
+        }));
+    }
+
+    Ok(effects)
+}
+
+fn parse_hues(log: Log, rng: &mut impl Rng, param: &Value) -> BaseResult<Vec<f64>> {
+    if let Some(hues) = parse_value_as_f64_sequence_complex(log, rng, param, "hues")? {
+        return Ok(hues);
+    }
+
+    if let Some(n) = parse_property_as_u64_complex(log, rng, param, "evenly")? {
+        let offset = parse_property_as_f64_complex(log, rng, param, "offset")?.unwrap_or(0.0);
+        return Ok((0..n)
+            .map(|i| (offset + i as f64 * 360.0 / n as f64).rem_euclid(360.0))
+            .collect());
+    }
+
+    if let Some(harmony) = parse_property_as_str(log, param, "harmony")? {
+        let angle = parse_property_as_f64_complex(log, rng, param, "angle")?.unwrap_or(30.0);
+        let offsets = harmony_offsets(&harmony, angle)
+            .unwrap_or_else(|| panic!("{harmony} is not a valid harmony."));
+        let seed_hue = parse_seed_hue(log, rng, param)?;
+
+        return Ok([vec![0.0], offsets]
+            .concat()
+            .into_iter()
+            .map(|offset| (seed_hue + offset).rem_euclid(360.0))
+            .collect());
+    }
+
+    if let Some(palette) = param.get("from-palette") {
+        let palette = match palette.as_str() {
+            Some("latest") => log
+                .latest_palette()
+                .cloned()
+                .ok_or("[quantize-hue.from-palette] is `latest`, but no palette was used yet.")?,
+            Some(other) => {
+                panic!("{other} is not valid for [from-palette] - expected `latest` or a palette.")
+            }
+            None => build_palette(log, rng, palette)?,
+        };
+
+        let mut hues = palette
+            .into_iter()
+            .map(|colour| IntoColor::<Lch>::into_color(colour))
+            .filter(|colour| colour.chroma >= GREY_CHROMA)
+            .map(|colour| colour.hue.into_positive_degrees() as f64)
+            .collect::<Vec<_>>();
+        hues.sort_by(|a, b| a.total_cmp(b));
+        hues.dedup_by(|a, b| (*a - *b).abs() < 0.5);
+
+        return Ok(hues);
+    }
 
-    Ok(QuantizeHue::with_hues(hues))
+    panic!("[quantize-hue] needs [hues], or one of [evenly], [harmony] or [from-palette].")
 }
@@ -1,10 +1,11 @@
+use common_utils::naming::{describe_palette, nearest_name};
 use image_effects::dither::ordered::{Ordered, OrderedStrategy};
 use palette::named;
 use rand::{seq::SliceRandom, Rng};
 use serde_yaml::Value;
 
 use crate::{
-    effects::{BaseResult, Log},
+    effects::{cvd::Deficiency, BaseResult, Log},
     parsers::{
         modifiers::{
             checker::parse_checker,
@@ -123,6 +124,13 @@ impl From<&str> for OrderedKind {
     }
 }
 
+/// Below this difference (CIEDE2000), two palette colours are considered indistinguishable.
+const INDISTINGUISHABLE_DELTA: f32 = 3.0;
+/// How many indistinguishable pairs are listed per deficiency, before the rest are summarised.
+const MAX_LISTED_PAIRS: usize = 8;
+/// How many distinct colour names the palette is summarised with.
+const MAX_DESCRIBED_NAMES: usize = 12;
+
 pub fn parse_ordered(log: Log, rng: &mut impl Rng, value: &Value) -> BaseResult<Ordered> {
     let value = value.get("ordered").unwrap();
 
@@ -146,9 +154,36 @@ pub fn parse_ordered(log: Log, rng: &mut impl Rng, value: &Value) -> BaseResult<
 
         log.state_property(
             format!("#{i:03}"),
-            format!("RGB: ({hexcode}) -> ({red:.2},{green:.2},{blue:.2})"),
+            format!(
+                "RGB: ({hexcode}, {}) -> ({red:.2},{green:.2},{blue:.2})",
+                nearest_name(col)
+            ),
+        )?;
+    }
+    log.state_property("names", describe_palette(&palette, MAX_DESCRIBED_NAMES))?;
+    for deficiency in Deficiency::ALL {
+        let pairs = deficiency.indistinguishable_pairs(&palette, INDISTINGUISHABLE_DELTA);
+        if pairs.is_empty() {
+            continue;
+        }
+
+        let mut listed = pairs
+            .iter()
+            .take(MAX_LISTED_PAIRS)
+            .map(|(i, j, delta)| format!("#{i:03} & #{j:03} (ΔE {delta:.2})"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        if pairs.len() > MAX_LISTED_PAIRS {
+            listed += &format!(" and {} more", pairs.len() - MAX_LISTED_PAIRS);
+        }
+
+        log.warn_log(
+            "palette",
+            format!("indistinguishable with {}: {listed}", deficiency.name()),
         )?;
+        log.state_property(deficiency.name(), format!("indistinguishable: {listed}"))?;
     }
+    log.record_dithered(&palette);
     log.end_category()?;
 
     let mirror = value.get("mirror").map(|mirror| {
@@ -0,0 +1,137 @@
+use std::{collections::HashMap, sync::RwLock};
+
+use common_utils::generator::{
+    extract::{ExtractMethod, ExtractSpace},
+    Extract, PaletteGenerator,
+};
+use image::DynamicImage;
+use lazy_static::lazy_static;
+use palette::Srgb;
+use rand::Rng;
+use serde_yaml::Mapping;
+
+use crate::{
+    effects::{BaseResult, Log},
+    parsers::util::logless::parse_u64_complex,
+    source::ImageResult,
+};
+
+// EXTRACTED PALETTES
+// ==================
+// Palettes can be extracted from an image, via:
+//
+//      palette:
+//        type: extract
+//        from: source          # or a path / url to any other image.
+//        count: 8              # how many colours to extract - can be a range or choice.
+//        method: kmeans        # kmeans, median-cut or octree.
+//        space: lab            # lab or oklab - which space colours are compared in.
+//
+// `source` refers to the configuration's own source - for gifs, its first frame.
+// Paths are relative to the configuration file the palette is written in.
+// Images are shrunk before extraction, and a random sample of their pixels is used,
+//  picked by the run's RNG - as is k-means' initialisation. So with a [seed] set,
+//  extraction is reproducible.
+
+lazy_static! {
+    /// Pixels of every image extracted from so far, keyed by `source` or their path/url.
+    static ref IMAGES: RwLock<HashMap<String, Vec<Srgb>>> = RwLock::new(HashMap::new());
+}
+
+/// Registers the configuration's source, so it can be extracted from via `from: source`.
+pub fn set_source(media: &ImageResult) {
+    let image = match media {
+        ImageResult::Image(image) => image.clone(),
+        ImageResult::Gif(frames) => match frames.first() {
+            Some(frame) => DynamicImage::ImageRgba8(frame.buffer().clone()),
+            None => return,
+        },
+    };
+
+    IMAGES
+        .write()
+        .unwrap()
+        .insert("source".into(), Extract::pixels_of(&image));
+}
+
+fn load_pixels(from: &str) -> BaseResult<Vec<Srgb>> {
+    if let Some(pixels) = IMAGES.read().unwrap().get(from) {
+        return Ok(pixels.clone());
+    }
+
+    if from == "source" {
+        return Err("[palette.from] is `source`, but no source has been loaded.".into());
+    }
+
+    let pixels = Extract::load_pixels(from)?;
+    IMAGES
+        .write()
+        .unwrap()
+        .insert(from.to_string(), pixels.clone());
+
+    Ok(pixels)
+}
+
+pub fn parse_extract_palette(
+    log: Log,
+    rng: &mut impl Rng,
+    palette: &Mapping,
+) -> BaseResult<Vec<Srgb>> {
+    let from = palette.get("from").map_or("source", |from| {
+        from.as_str()
+            .expect("[palette.from] must be `source`, or a path/url to an image.")
+    });
+
+    let count = palette
+        .get("count")
+        .map_or(8, |count| parse_u64_complex(rng, count)) as usize;
+
+    if count == 0 {
+        return Err("[palette.count] must be at least 1.".into());
+    }
+
+    let method = match palette
+        .get("method")
+        .map(|method| method.as_str().expect("[palette.method] must be a string."))
+    {
+        None => ExtractMethod::KMeans,
+        Some(method) => ExtractMethod::from_name(method).unwrap_or_else(|| {
+            panic!(
+                "{method} is not a valid extraction method - expected kmeans, median-cut or octree."
+            )
+        }),
+    };
+
+    let space = match palette
+        .get("space")
+        .map(|space| space.as_str().expect("[palette.space] must be a string."))
+    {
+        None => ExtractSpace::Lab,
+        Some(space) => ExtractSpace::from_name(space).unwrap_or_else(|| {
+            panic!("{space} is not a valid colour space - expected lab or oklab.")
+        }),
+    };
+
+    let pixels = load_pixels(from)?;
+    if pixels.is_empty() {
+        return Err(format!("[palette.from] image [{from}] has no opaque pixels.").into());
+    }
+
+    let colours = Extract {
+        pixels,
+        count,
+        method,
+        space,
+    }
+    .generate(rng)?;
+
+    log.info_log(
+        "palette",
+        format!(
+            "extracted {} colours from [{from}] via {method:?} in {space:?}",
+            colours.len()
+        ),
+    )?;
+
+    Ok(colours)
+}
@@ -0,0 +1,54 @@
+use common_utils::generator::FilePalette;
+use palette::Srgb;
+use serde_yaml::Mapping;
+
+use crate::effects::{BaseResult, Log};
+
+// PALETTE FILES
+// =============
+// Palettes can be loaded from files, via `palette: { type: file, path: ./palette.gpl }`.
+// Paths are relative to the configuration file the palette is written in.
+//
+// GIMP (.gpl), JASC & RIFF (.pal), Adobe (.ase), Paint.NET (.txt), hex (.hex) and swatch
+//  images (.png) are all read - see [common_utils::generator::file] for the details.
+//
+// Palette names, colour names and comments are kept, and written to the app log
+//  whenever the palette is used.
+
+pub fn parse_file_palette(log: Log, palette: &Mapping) -> BaseResult<Vec<Srgb>> {
+    let path = palette
+        .get("path")
+        .expect("if [palette.type] is \"file\", [palette.path] must be present.")
+        .as_str()
+        .expect("[palette.path] must be a string.");
+
+    let (format, file) = FilePalette::new(path).read()?;
+
+    log.info_log(
+        "palette",
+        format!(
+            "using {} palette file [{path}]{}",
+            format.name(),
+            file.name
+                .as_ref()
+                .map(|name| format!(" - {name}"))
+                .unwrap_or_default()
+        ),
+    )?;
+
+    for comment in &file.comments {
+        log.info_log("palette", format!("    # {comment}"))?;
+    }
+
+    for (i, (colour, name)) in file.colours.iter().enumerate() {
+        if let Some(name) = name {
+            let (r, g, b) = colour.into_format::<u8>().into_components();
+            log.info_log(
+                "palette",
+                format!("    #{i:0>3}: #{r:02x}{g:02x}{b:02x} {name}"),
+            )?;
+        }
+    }
+
+    Ok(file.srgb())
+}
@@ -0,0 +1,245 @@
+use common_utils::colour::DeltaMetric;
+use palette::{IntoColor, Lab, LinSrgb, Srgb};
+use rand::Rng;
+use serde_yaml::Value;
+
+use crate::{
+    effects::{BaseResult, Log},
+    parsers::util::logless::{parse_f64_complex, parse_u64_complex},
+};
+
+// GUARDS
+// ======
+// Any palette can have a [guards] block - constraints it has to meet. A palette that fails
+//  any of them is thrown away and generated again (including any [inject] and [post]), up to
+//  [retries] times. If it still fails after that, the last attempt is kept, with a warning.
+//  Only the attempt that's kept is written to log.log - rejected ones are noted in app.log.
+//
+//      palette:
+//        type: random_v2
+//        config: ...
+//        guards:
+//          min-lightness-span: 60
+//          min-delta: 8
+//          min-size: 4
+//          max-size: 16
+//          dark-anchor: 20
+//          light-anchor: 85
+//          min-contrast: 4.5
+//          retries: 10
+//
+// [min-lightness-span] = the lightest colour must be at least this much lighter than the
+//      darkest. (lightness from 0 to 100)
+// [min-delta] = every pair of colours must be at least this far apart. (ΔE, ciede2000)
+// [min-size], [max-size] = how many colours the palette can have.
+// [dark-anchor] = at least one colour must be this dark or darker.
+// [light-anchor] = at least one colour must be this light or lighter.
+// [min-contrast] = the WCAG contrast ratio between the darkest and lightest colours -
+//      from 1 (none) to 21 (black on white). 4.5 is WCAG's minimum for normal text.
+// [retries] = how many times to regenerate before giving up. (10 by default)
+//
+// These are a gentler alternative to random_v2's [lum_safeguard] and [extremes] flags,
+//  which pad the palette with colours instead.
+
+const DEFAULT_RETRIES: u64 = 10;
+
+#[derive(Debug, Default)]
+pub struct Guards {
+    pub min_lightness_span: Option<f32>,
+    pub min_delta: Option<f32>,
+    pub min_size: Option<usize>,
+    pub max_size: Option<usize>,
+    pub dark_anchor: Option<f32>,
+    pub light_anchor: Option<f32>,
+    pub min_contrast: Option<f32>,
+    pub retries: u64,
+}
+
+impl Guards {
+    pub fn parse(rng: &mut impl Rng, guards: &Value) -> Self {
+        let guards = guards
+            .as_mapping()
+            .expect("[palette.guards] must be a mapping.");
+
+        let f32_of = |rng: &mut _, name: &str| {
+            guards
+                .get(name)
+                .map(|value| parse_f64_complex(rng, value) as f32)
+        };
+        let usize_of = |rng: &mut _, name: &str| {
+            guards
+                .get(name)
+                .map(|value| parse_u64_complex(rng, value) as usize)
+        };
+
+        Guards {
+            min_lightness_span: f32_of(rng, "min-lightness-span"),
+            min_delta: f32_of(rng, "min-delta"),
+            min_size: usize_of(rng, "min-size"),
+            max_size: usize_of(rng, "max-size"),
+            dark_anchor: f32_of(rng, "dark-anchor"),
+            light_anchor: f32_of(rng, "light-anchor"),
+            min_contrast: f32_of(rng, "min-contrast"),
+            retries: guards
+                .get("retries")
+                .map_or(DEFAULT_RETRIES, |retries| parse_u64_complex(rng, retries)),
+        }
+    }
+
+    /// Returns a description of every guard the palette fails.
+    pub fn check(&self, palette: &[Srgb]) -> Vec<String> {
+        let mut failures = vec![];
+
+        let lightness = palette
+            .iter()
+            .map(|colour| IntoColor::<Lab>::into_color(*colour).l)
+            .collect::<Vec<_>>();
+        let darkest = lightness.iter().copied().fold(f32::MAX, f32::min);
+        let lightest = lightness.iter().copied().fold(f32::MIN, f32::max);
+
+        if let Some(min) = self.min_size {
+            if palette.len() < min {
+                failures.push(format!(
+                    "has {} colours, needs at least {min}",
+                    palette.len()
+                ));
+            }
+        }
+
+        if let Some(max) = self.max_size {
+            if palette.len() > max {
+                failures.push(format!(
+                    "has {} colours, needs at most {max}",
+                    palette.len()
+                ));
+            }
+        }
+
+        if palette.is_empty() {
+            return failures;
+        }
+
+        if let Some(span) = self.min_lightness_span {
+            if lightest - darkest < span {
+                failures.push(format!(
+                    "lightness spans {:.1}, needs at least {span}",
+                    lightest - darkest
+                ));
+            }
+        }
+
+        if let Some(anchor) = self.dark_anchor {
+            if darkest > anchor {
+                failures.push(format!(
+                    "darkest colour is at {darkest:.1}, needs one at {anchor} or below"
+                ));
+            }
+        }
+
+        if let Some(anchor) = self.light_anchor {
+            if lightest < anchor {
+                failures.push(format!(
+                    "lightest colour is at {lightest:.1}, needs one at {anchor} or above"
+                ));
+            }
+        }
+
+        if let Some(min) = self.min_delta {
+            let closest = palette
+                .iter()
+                .enumerate()
+                .flat_map(|(i, a)| {
+                    palette[i + 1..]
+                        .iter()
+                        .map(|b| DeltaMetric::Ciede2000.delta(*a, *b))
+                })
+                .fold(f32::MAX, f32::min);
+
+            if closest < min {
+                failures.push(format!(
+                    "closest colours are ΔE {closest:.2} apart, needs at least {min}"
+                ));
+            }
+        }
+
+        if let Some(min) = self.min_contrast {
+            let contrast = contrast_ratio(palette);
+            if contrast < min {
+                failures.push(format!(
+                    "contrast is {contrast:.2}:1, needs at least {min}:1"
+                ));
+            }
+        }
+
+        failures
+    }
+}
+
+/// The WCAG contrast ratio between the darkest and lightest colours of a palette.
+pub fn contrast_ratio(palette: &[Srgb]) -> f32 {
+    let luminance = palette
+        .iter()
+        .map(|colour| {
+            let linear: LinSrgb = colour.into_linear();
+            0.2126 * linear.red + 0.7152 * linear.green + 0.0722 * linear.blue
+        })
+        .collect::<Vec<_>>();
+
+    let darkest = luminance.iter().copied().fold(f32::MAX, f32::min);
+    let lightest = luminance.iter().copied().fold(f32::MIN, f32::max);
+
+    (lightest + 0.05) / (darkest + 0.05)
+}
+
+/// Generates palettes via `generate` until one passes the guards, or retries run out.
+pub fn generate_guarded<R: Rng>(
+    log: Log,
+    rng: &mut R,
+    guards: &Value,
+    mut generate: impl FnMut(Log, &mut R) -> BaseResult<Vec<Srgb>>,
+) -> BaseResult<Vec<Srgb>> {
+    let guards = Guards::parse(rng, guards);
+
+    // rejected attempts are taken back out of the log, so only the palette kept shows up.
+    let checkpoint = log.checkpoint();
+
+    let mut attempt = 0;
+    loop {
+        let palette = generate(log, rng)?;
+        let failures = guards.check(&palette);
+
+        if failures.is_empty() {
+            log.begin_category("guards")?;
+            log.state_property("attempts", attempt + 1)?;
+            log.end_category()?;
+            return Ok(palette);
+        }
+
+        if attempt >= guards.retries {
+            log.warn_log(
+                "guards",
+                format!(
+                    "palette still fails its guards after {} attempts, keeping it: {}",
+                    attempt + 1,
+                    failures.join("; ")
+                ),
+            )?;
+            log.begin_category("guards")?;
+            log.state_property("attempts", attempt + 1)?;
+            log.state_property("failing", failures.join("; "))?;
+            log.end_category()?;
+            return Ok(palette);
+        }
+
+        log.rollback(checkpoint);
+        log.info_log(
+            "guards",
+            format!(
+                "attempt {} failed, regenerating: {}",
+                attempt + 1,
+                failures.join("; ")
+            ),
+        )?;
+        attempt += 1;
+    }
+}
@@ -0,0 +1,135 @@
+use common_utils::colour::DeltaMetric;
+use palette::{IntoColor, Lch, Srgb};
+use rand::Rng;
+use serde_yaml::Value;
+
+use crate::{
+    effects::{BaseResult, Log},
+    parsers::util::logless::parse_f64_complex,
+};
+
+use super::{build_palette, parse_colour, GenerationSpace};
+
+// INJECTION
+// =========
+// Any palette can have an [inject] block, which adds extra colours once it's generated -
+//  before any [post] processing:
+//
+//      palette:
+//        type: random_v2
+//        config: ...
+//        inject:
+//          colours: ["#000", { rgb: FFFFFF }, rebeccapurple]
+//          palettes: [{ type: named, name: corru }]
+//          lightness: [5, 95]
+//          dedupe: 3.0
+//
+// [colours] = any colours, in any colour syntax.
+// [palettes] = any palettes - every colour from each is injected.
+// [lightness] = if given, each injected colour is added at each of these lightnesses instead,
+//      keeping its hue and chroma (as far as sRGB allows). handy for anchoring a palette with
+//      a dark and a light shade of an accent.
+// [dedupe] = skip injected colours within this ΔE (ciede2000) of a colour already in the
+//      palette - including ones injected before it. `true` uses a ΔE of 2.
+//
+// A list on its own is read as [colours]. For random_v2, [palette.config.inject] still works.
+
+const DEFAULT_DEDUPE_DELTA: f32 = 2.0;
+
+pub fn apply_inject(
+    log: Log,
+    rng: &mut impl Rng,
+    inject: &Value,
+    palette: Vec<Srgb>,
+) -> BaseResult<Vec<Srgb>> {
+    // everything's parsed before the category is opened, so an invalid colour can't leave it open.
+    let (colours, palettes) = match inject {
+        Value::Sequence(_) => (Some(inject), None),
+        Value::Mapping(mapping) => (mapping.get("colours"), mapping.get("palettes")),
+        _ => panic!("[palette.inject] must be a mapping, or a list of colours."),
+    };
+
+    let mut injected = vec![];
+
+    if let Some(colours) = colours {
+        let colours = colours
+            .as_sequence()
+            .expect("[palette.inject.colours] must be a list of colours.");
+        for (i, colour) in colours.iter().enumerate() {
+            injected.extend(
+                parse_colour(log, rng, colour)
+                    .map_err(|err| format!("[palette.inject.colours.{i}] {err}"))?,
+            );
+        }
+    }
+
+    if let Some(palettes) = palettes {
+        let palettes = palettes
+            .as_sequence()
+            .expect("[palette.inject.palettes] must be a list of palettes.");
+        for palette in palettes {
+            injected.extend(build_palette(log, rng, palette)?);
+        }
+    }
+
+    let lightness = inject.get("lightness").map(|lightness| {
+        lightness
+            .as_sequence()
+            .expect("[palette.inject.lightness] must be a list of lightnesses.")
+            .iter()
+            .map(|l| parse_f64_complex(rng, l))
+            .collect::<Vec<_>>()
+    });
+
+    if let Some(lightness) = &lightness {
+        injected = injected
+            .into_iter()
+            .flat_map(|colour| {
+                let lch: Lch = colour.into_color();
+                lightness
+                    .iter()
+                    .map(|l| {
+                        GenerationSpace::Lch.map_to_srgb(Lch::new(*l as f32, lch.chroma, lch.hue))
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+    }
+
+    let dedupe = match inject.get("dedupe") {
+        None | Some(Value::Bool(false)) => None,
+        Some(Value::Bool(true)) => Some(DEFAULT_DEDUPE_DELTA),
+        Some(delta) => Some(parse_f64_complex(rng, delta) as f32),
+    };
+
+    log.begin_category("inject")?;
+    log.state_property("colours", injected.len())?;
+    if let Some(lightness) = &lightness {
+        log.state_property("lightness", format!("{lightness:?}"))?;
+    }
+
+    let mut palette = palette;
+    let mut skipped = 0;
+
+    for colour in injected {
+        let duplicate = dedupe.is_some_and(|delta| {
+            palette
+                .iter()
+                .any(|existing| DeltaMetric::Ciede2000.delta(*existing, colour) < delta)
+        });
+
+        if duplicate {
+            skipped += 1;
+        } else {
+            palette.push(colour);
+        }
+    }
+
+    if let Some(delta) = dedupe {
+        log.state_property("deduped", format!("{skipped} (ΔE < {delta:.2})"))?;
+    }
+    log.state_property("after", palette.len())?;
+    log.end_category()?;
+
+    Ok(palette)
+}
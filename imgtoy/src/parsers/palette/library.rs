@@ -0,0 +1,221 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::RwLock,
+};
+
+use common_utils::generator::{Named, PaletteGenerator};
+use lazy_static::lazy_static;
+use palette::Srgb;
+use rand::{seq::SliceRandom, Rng};
+use serde_yaml::{Mapping, Value};
+
+use crate::{
+    config::format::read_config,
+    effects::{BaseResult, Log},
+    parsers::palette::parse_colour,
+};
+
+// NAMED PALETTES
+// ==============
+// Palettes can be referred to by name, via `palette: { type: named, name: nightlife }`.
+// The name can also be a list, in which case one is picked at random.
+//
+// The library starts off with the curated palettes from [common_utils::palette::palettes],
+//  via [common_utils::generator::Named].
+// More can be added from any directories listed in the top-level [palette-library]:
+//
+//      palette-library: ./palettes     # relative to the configuration file.
+//
+// Every YAML (or JSON/TOML) file in there defines a single palette:
+//
+//      # ./palettes/sunrise.yaml
+//      name: sunrise       # optional - defaults to the file name.
+//      colours:
+//        - rgb: FFB347
+//        - { rgb: 6A0DAD, shades: 5 }
+//
+// Their colours accept the same syntax as a "specified" palette, and are parsed whenever
+//  the palette is used - so any random colours are re-rolled each time.
+// User palettes override built-in ones with the same name.
+
+pub struct UserPalette {
+    source: PathBuf,
+    colours: Vec<Value>,
+}
+
+/// User palettes - built-in ones are left to [common_utils::generator::Named].
+#[derive(Default)]
+pub struct PaletteLibrary {
+    palettes: BTreeMap<String, UserPalette>,
+}
+
+lazy_static! {
+    static ref LIBRARY: RwLock<PaletteLibrary> = RwLock::new(PaletteLibrary::default());
+}
+
+impl PaletteLibrary {
+    fn load_file(&mut self, path: &Path, warnings: &mut Vec<String>) -> BaseResult<()> {
+        let config = read_config(path)?;
+
+        let name = match config.get("name") {
+            Some(name) => name
+                .as_str()
+                .ok_or_else(|| format!("{}: [name] must be a string.", path.display()))?
+                .to_string(),
+            None => path
+                .file_stem()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string(),
+        };
+
+        let colours = config
+            .get("colours")
+            .and_then(|colours| colours.as_sequence())
+            .ok_or_else(|| {
+                format!(
+                    "{}: [colours] must be present, as a list of valid colours.",
+                    path.display()
+                )
+            })?
+            .clone();
+
+        let previous = self.palettes.insert(
+            name.clone(),
+            UserPalette {
+                source: path.to_path_buf(),
+                colours,
+            },
+        );
+
+        match previous {
+            Some(UserPalette { source, .. }) => warnings.push(format!(
+                "{}: palette [{name}] overrides the one from {}.",
+                path.display(),
+                source.display()
+            )),
+            None if Named::available().contains(&name) => warnings.push(format!(
+                "{}: palette [{name}] overrides the built-in palette of the same name.",
+                path.display()
+            )),
+            None => {}
+        }
+
+        Ok(())
+    }
+}
+
+/// Adds every palette file within `dir` to the library, returning how many were added.
+pub fn load_library_dir(dir: impl AsRef<Path>, warnings: &mut Vec<String>) -> BaseResult<usize> {
+    let dir = dir.as_ref();
+    let mut paths = std::fs::read_dir(dir)
+        .map_err(|err| format!("could not read palette library [{}]: {err}", dir.display()))?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // sorted, so overrides between files are predictable.
+    paths.sort();
+
+    let mut library = LIBRARY.write().unwrap();
+    let mut loaded = 0;
+
+    for path in paths {
+        let is_palette = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| matches!(ext, "yaml" | "yml" | "json" | "toml"));
+
+        if path.is_file() && is_palette {
+            library.load_file(&path, warnings)?;
+            loaded += 1;
+        }
+    }
+
+    Ok(loaded)
+}
+
+/// Loads the directories listed in the top-level `palette-library`, if any.
+pub fn load_library(config: &Value, warnings: &mut Vec<String>) -> BaseResult<usize> {
+    let dirs = match config.get("palette-library") {
+        None => vec![],
+        Some(Value::String(dir)) => vec![dir.clone()],
+        Some(Value::Sequence(dirs)) => dirs
+            .iter()
+            .map(|dir| {
+                dir.as_str()
+                    .expect("[palette-library] entries must be paths.")
+                    .to_string()
+            })
+            .collect(),
+        Some(_) => panic!("[palette-library] must be a path, or a list of paths."),
+    };
+
+    let mut loaded = 0;
+    for dir in dirs {
+        loaded += load_library_dir(dir, warnings)?;
+    }
+
+    Ok(loaded)
+}
+
+pub fn parse_named_palette(
+    log: Log,
+    rng: &mut impl Rng,
+    palette: &Mapping,
+) -> BaseResult<Vec<Srgb>> {
+    let names = match palette
+        .get("name")
+        .expect("if [palette.type] is \"named\", [palette.name] must be present.")
+    {
+        Value::String(name) => vec![name.clone()],
+        Value::Sequence(names) => names
+            .iter()
+            .map(|name| {
+                name.as_str()
+                    .expect("[palette.name] must be a name, or a list of names.")
+                    .to_string()
+            })
+            .collect(),
+        _ => panic!("[palette.name] must be a name, or a list of names."),
+    };
+
+    let name = names
+        .choose(rng)
+        .expect("[palette.name] must have at least one name.");
+
+    log.info_log("palette", format!("using named palette [{name}]"))?;
+
+    let library = LIBRARY.read().unwrap();
+
+    let colours = match library.palettes.get(name) {
+        Some(UserPalette { colours, .. }) => colours.clone(),
+        None if Named::available().contains(name) => {
+            return Named::new(name.as_str()).generate(rng);
+        }
+        None => {
+            let mut available = library.palettes.keys().cloned().collect::<Vec<_>>();
+            available.extend(Named::available());
+            available.sort();
+            available.dedup();
+            return Err(format!(
+                "[palette.name] {name} is not a known palette. available are: {}",
+                available.join(", ")
+            )
+            .into());
+        }
+    };
+
+    // the library isn't kept locked while parsing the colours.
+    drop(library);
+
+    Ok(colours
+        .iter()
+        .enumerate()
+        .map(|(i, colour)| {
+            parse_colour(log, rng, colour)
+                .map_err(|err| format!("colour #{i} of the [{name}] palette: {err}").into())
+        })
+        .collect::<BaseResult<Vec<_>>>()?
+        .concat())
+}
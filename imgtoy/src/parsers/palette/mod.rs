@@ -0,0 +1,260 @@
+use std::fmt::Display;
+
+use common_utils::{
+    colour::parse_rgb,
+    generator::{
+        random_v2::RandomV2, reader::ConfigReader, GeneratorResult, PaletteGenerator, RandomV1,
+    },
+};
+use image_effects::prelude::IntoGradientLch;
+use palette::{IntoColor, Lch, Srgb};
+use rand::{Rng, RngCore};
+use serde_yaml::Value;
+
+use crate::{
+    effects::{BaseResult, Log},
+    parsers::util::{
+        logless::parse_f64_complex, parse_property_as_f64_complex, parse_property_as_str,
+        parse_property_as_u64_complex, parse_value_as_f64_sequence_complex,
+    },
+};
+
+pub mod extract;
+pub mod file;
+pub mod guards;
+pub mod inject;
+pub mod library;
+pub mod post;
+pub mod ramp;
+
+pub use common_utils::generator::{
+    random_v1::gen_with_lightness, random_v2::harmony_offsets, space::GenerationSpace,
+};
+
+pub fn parse_palette(
+    log: Log,
+    rng: &mut impl Rng,
+    param: &serde_yaml::Value,
+) -> BaseResult<Vec<Srgb>> {
+    let palette = build_palette(log, rng, param);
+    if let Ok(palette) = &palette {
+        log.record_palette(palette);
+    }
+    palette
+}
+
+/// Same as `parse_palette`, but without recording the palette as one used this iteration -
+/// for palettes that only feed into another.
+pub fn build_palette(
+    log: Log,
+    rng: &mut impl Rng,
+    param: &serde_yaml::Value,
+) -> BaseResult<Vec<Srgb>> {
+    match param.get("guards") {
+        Some(guards) => guards::generate_guarded(log, rng, guards, |log, rng| {
+            generate_palette(log, rng, param)
+        }),
+        None => generate_palette(log, rng, param),
+    }
+}
+
+fn generate_palette(
+    log: Log,
+    rng: &mut impl Rng,
+    param: &serde_yaml::Value,
+) -> BaseResult<Vec<Srgb>> {
+    log.pause();
+    let palette = if let Some(palette) = param.as_mapping() {
+        let palette_type = palette
+            .get("type")
+            .expect("[palette] requires a [.type] to be specified.")
+            .as_str()
+            .expect("[palette.type] must be a string.");
+
+        Ok(match palette_type {
+            "random_v1" => RandomV1.generate(rng)?,
+            "specified" => {
+                let colours = palette
+                    .get("colours")
+                    .expect(
+                        "if [palette.type] is \"specified\", [palette.colours] must be present.",
+                    )
+                    .as_sequence()
+                    .expect("[palette.colours] must be a list of valid colours");
+
+                colours
+                    .iter()
+                    .enumerate()
+                    .map(|(i, colour)| {
+                        parse_colour(log, rng, colour).map_err(|err| {
+                            format!("colour #{i} of the palette at [{}]: {err}", log.path()).into()
+                        })
+                    })
+                    .collect::<BaseResult<Vec<_>>>()?
+                    .concat()
+            }
+            "named" => library::parse_named_palette(log, rng, palette)?,
+            "file" => file::parse_file_palette(log, palette)?,
+            "extract" => extract::parse_extract_palette(log, rng, palette)?,
+            "random_v2" => {
+                let config = palette.get("config").expect(
+                    "if [palette.type] is \"random_v2\", [palette.config] must be present.",
+                );
+
+                RandomV2::read(config, &mut ComplexReader { log, rng })?.generate(rng)?
+            }
+            _ => {
+                panic!("{palette_type} is not a valid palette type.");
+            }
+        })
+    } else if param.as_str() == Some("random") {
+        Err(format!(
+            "[palette] in [{}] must be a mapping - `palette: random` is from version 1 configs, \
+             and is now `palette: {{ type: random_v1 }}`. `imgtoy migrate <config>` can rewrite \
+             it for you.",
+            log.path()
+        )
+        .into())
+    } else {
+        Err(format!(
+            "[palette] in [{}] must be a mapping with a [type], such as \
+             `{{ type: named, name: corru }}` - found {param:?}.",
+            log.path()
+        )
+        .into())
+    };
+    log.unpause();
+    let inject = param
+        .get("inject")
+        .or_else(|| param.get("config").and_then(|config| config.get("inject")));
+    let palette = match inject {
+        Some(inject) => palette.and_then(|palette| inject::apply_inject(log, rng, inject, palette)),
+        None => palette,
+    };
+    match param.get("post") {
+        Some(post) => palette.and_then(|palette| post::apply_post(log, rng, post, palette)),
+        None => palette,
+    }
+}
+
+// RANDOM V2
+// =========
+// The config - and every strategy within it - is described alongside the generator, in
+//  [common_utils::generator::random_v2]. Any number in it can also be a range or a choice.
+
+/// Reads random_v2's config through [common_utils::generator::random_v2] - resolving ranges and
+/// choices as they're read, and logging everything.
+struct ComplexReader<'a, 'b, R: Rng> {
+    log: Log<'a>,
+    rng: &'b mut R,
+}
+
+impl<R: Rng> ConfigReader for ComplexReader<'_, '_, R> {
+    fn f64(&mut self, value: &Value, name: &str) -> GeneratorResult<Option<f64>> {
+        parse_property_as_f64_complex(self.log, self.rng, value, name)
+    }
+
+    fn u64(&mut self, value: &Value, name: &str) -> GeneratorResult<Option<u64>> {
+        parse_property_as_u64_complex(self.log, self.rng, value, name)
+    }
+
+    fn f64_list(&mut self, value: &Value, name: &str) -> GeneratorResult<Option<Vec<f64>>> {
+        parse_value_as_f64_sequence_complex(self.log, self.rng, value, name)
+    }
+
+    fn str(&mut self, value: &Value, name: &str) -> GeneratorResult<Option<String>> {
+        parse_property_as_str(self.log, value, name)
+    }
+
+    fn seed_hue(&mut self, value: &Value) -> GeneratorResult<Option<f64>> {
+        parse_seed_hue(self.log, self.rng, value).map(Some)
+    }
+
+    fn rng(&mut self) -> &mut dyn RngCore {
+        self.rng
+    }
+
+    fn state(&mut self, name: &str, value: &dyn Display) -> GeneratorResult<()> {
+        self.log.state_property(name, value)?;
+        Ok(())
+    }
+
+    fn begin(&mut self, name: &str) -> GeneratorResult<()> {
+        self.log.begin_category(name)?;
+        Ok(())
+    }
+
+    fn end(&mut self) -> GeneratorResult<()> {
+        self.log.end_category()?;
+        Ok(())
+    }
+}
+
+/// Reads [seed-hue] - a (complex) hue, or a colour to take the hue of. Random by default.
+pub fn parse_seed_hue(log: Log, rng: &mut impl Rng, value: &Value) -> BaseResult<f64> {
+    let seed_hue = match value.get("seed-hue") {
+        None => rng.gen_range(0.0..360.0),
+        Some(colour)
+            if colour.is_string() || (colour.is_mapping() && colour.get("min").is_none()) =>
+        {
+            let colour = *parse_colour(log, rng, colour)?
+                .first()
+                .expect("[seed-hue] must be a colour with at least one shade.");
+            let colour: Lch = colour.into_color();
+            colour.hue.into_positive_degrees() as f64
+        }
+        Some(hue) if hue.is_number() || hue.is_mapping() || hue.is_sequence() => {
+            parse_f64_complex(rng, hue)
+        }
+        Some(other) => {
+            return Err(format!(
+                "{other:?} is not a valid [seed-hue] - expected a hue, range, choice or colour."
+            )
+            .into())
+        }
+    };
+
+    log.state_property("seed-hue", seed_hue)?;
+
+    Ok(seed_hue)
+}
+
+pub fn parse_colour(log: Log, rng: &mut impl Rng, param: &Value) -> BaseResult<Vec<Srgb>> {
+    Ok(if param.is_string() {
+        vec![parse_rgb(param)?]
+    } else if let Some(rgb) = param.get("rgb") {
+        let colour = parse_rgb(rgb).map_err(|err| format!("[rgb] {err}"))?;
+        let gradient = if let Some(shades) = param.get("shades") {
+            let shades = shades
+                .as_u64()
+                .expect("[palette.shades] must be a positive integer.");
+            colour.build_gradient_lch(shades as u16)
+        } else {
+            vec![colour]
+        };
+        gradient
+    } else if let Some(ramp) = param.get("ramp") {
+        ramp::parse_ramp(log, rng, ramp)?
+    } else if let Some(amnt) = parse_property_as_u64_complex(log, rng, param, "random")? {
+        let mut colours = vec![];
+        for _ in 0..amnt {
+            colours.push(Srgb::new(
+                rng.gen_range(0.0..=1.0),
+                rng.gen_range(0.0..=1.0),
+                rng.gen_range(0.0..=1.0),
+            ))
+        }
+
+        colours
+    } else {
+        return Err(format!(
+            "{param:?} is not a valid colour - expected a colour string, [rgb], [ramp] or [random]."
+        )
+        .into());
+    })
+}
+
+// COLOUR LITERALS
+// ===============
+// [rgb] (or a colour on its own, as a string) is read by [common_utils::colour] - which lists
+//  every syntax it accepts.
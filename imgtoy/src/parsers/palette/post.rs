@@ -0,0 +1,240 @@
+use common_utils::colour::DeltaMetric;
+use palette::{IntoColor, Lab, Lch, Srgb};
+use rand::Rng;
+use serde_yaml::Value;
+
+use crate::{
+    effects::{BaseResult, Log},
+    parsers::util::logless::{parse_f64_complex, parse_u64_complex},
+};
+
+// POST-PROCESSING
+// ===============
+// Any palette can have a [post] block, which is applied after it's generated:
+//
+//      palette:
+//        type: random_v2
+//        config: ...
+//        post:
+//          merge: { delta: 4.0, metric: ciede2000 }   # or just `merge: 4.0`
+//          cap: 12                                    # or `{ count: 12, metric: oklab }`
+//          sort: lightness                            # or `{ by: hue, descending: true }`
+//
+// The steps are always applied in this order:
+//  - merge: colours closer than [delta] to an earlier colour are merged into it (averaged).
+//      [metric] is either ciede2000 (the default), or oklab - the euclidean distance in
+//      Oklab, scaled by 100 so it's comparable to ciede2000.
+//  - cap: if there are more than N colours, N are picked via farthest-point selection -
+//      starting from the darkest, each pick is the colour furthest from those picked so far.
+//      Distances use [metric] - which defaults to merge's metric, if there is one.
+//  - sort: by lightness, or by hue - with greys first, by lightness.
+
+/// Reads a [metric] - ciede2000 by default.
+pub fn parse_delta_metric(value: Option<&Value>) -> DeltaMetric {
+    match value.map(|metric| metric.as_str().expect("[metric] must be a string.")) {
+        None => DeltaMetric::Ciede2000,
+        Some(metric) => DeltaMetric::from_name(metric).unwrap_or_else(|| {
+            panic!("{metric} is not a valid metric - expected ciede2000 or oklab.")
+        }),
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum SortBy {
+    Lightness,
+    Hue,
+}
+
+/// Greys don't have a meaningful hue, so they're sorted separately.
+const GREY_CHROMA: f32 = 5.0;
+
+pub fn apply_post(
+    log: Log,
+    rng: &mut impl Rng,
+    post: &Value,
+    palette: Vec<Srgb>,
+) -> BaseResult<Vec<Srgb>> {
+    let post = post
+        .as_mapping()
+        .expect("[palette.post] must be a mapping.");
+
+    // everything's parsed before the category is opened, so an invalid step can't leave it open.
+    let merge = post.get("merge").map(|merge| match merge {
+        Value::Mapping(merge) => (
+            parse_f64_complex(
+                rng,
+                merge
+                    .get("delta")
+                    .expect("[palette.post.merge] requires a [delta]."),
+            ),
+            parse_delta_metric(merge.get("metric")),
+        ),
+        delta => (parse_f64_complex(rng, delta), DeltaMetric::Ciede2000),
+    });
+
+    let cap = match post.get("cap") {
+        None => None,
+        Some(cap) => {
+            let (count, metric) = match cap {
+                Value::Mapping(cap) => (
+                    cap.get("count")
+                        .expect("[palette.post.cap] requires a [count]."),
+                    cap.get("metric"),
+                ),
+                count => (count, None),
+            };
+            let count = parse_u64_complex(rng, count) as usize;
+            if count == 0 {
+                return Err("[palette.post.cap] must be at least 1.".into());
+            }
+
+            // without its own [metric], cap measures colours the same way merge does.
+            let metric = match (metric, merge) {
+                (None, Some((_, metric))) => metric,
+                (metric, _) => parse_delta_metric(metric),
+            };
+            Some((count, metric))
+        }
+    };
+
+    let sort = post.get("sort").map(|sort| {
+        let (by, descending) = match sort {
+            Value::Mapping(sort) => (
+                sort.get("by")
+                    .expect("[palette.post.sort] requires a [by].")
+                    .as_str()
+                    .expect("[palette.post.sort.by] must be a string."),
+                sort.get("descending").is_some_and(|descending| {
+                    descending
+                        .as_bool()
+                        .expect("[palette.post.sort.descending] must be a boolean.")
+                }),
+            ),
+            by => (
+                by.as_str()
+                    .expect("[palette.post.sort] must be lightness or hue."),
+                false,
+            ),
+        };
+
+        let by = match by {
+            "lightness" => SortBy::Lightness,
+            "hue" => SortBy::Hue,
+            _ => panic!("{by} is not a valid sort - expected lightness or hue."),
+        };
+        (by, descending)
+    });
+
+    log.begin_category("post")?;
+    log.state_property("before", palette.len())?;
+
+    let mut palette = palette;
+
+    if let Some((delta, metric)) = merge {
+        palette = merge_close(&palette, delta as f32, metric);
+        log.state_property(
+            "merged",
+            format!("{} (ΔE < {delta:.2} via {metric:?})", palette.len()),
+        )?;
+    }
+
+    if let Some((cap, metric)) = cap {
+        palette = farthest_points(&palette, cap, metric);
+        log.state_property(
+            "capped",
+            format!("{} (at most {cap}, via {metric:?})", palette.len()),
+        )?;
+    }
+
+    if let Some((by, descending)) = sort {
+        sort_palette(&mut palette, by);
+        if descending {
+            palette.reverse();
+        }
+
+        log.state_property(
+            "sorted",
+            format!("by {by:?}{}", if descending { ", descending" } else { "" }).to_lowercase(),
+        )?;
+    }
+
+    log.state_property("after", palette.len())?;
+    log.end_category()?;
+
+    Ok(palette)
+}
+
+/// Merges each colour into the first earlier colour within `delta`, averaging them in Lab.
+pub fn merge_close(palette: &[Srgb], delta: f32, metric: DeltaMetric) -> Vec<Srgb> {
+    // each group keeps its current colour, the sum of its colours in Lab, and its size.
+    let mut groups: Vec<(Srgb, [f32; 3], f32)> = vec![];
+
+    for colour in palette {
+        let lab: Lab = (*colour).into_color();
+        let (l, a, b) = lab.into_components();
+
+        match groups
+            .iter_mut()
+            .find(|(group, _, _)| metric.delta(*group, *colour) < delta)
+        {
+            Some((group, sum, n)) => {
+                *sum = [sum[0] + l, sum[1] + a, sum[2] + b];
+                *n += 1.0;
+                *group = Lab::new(sum[0] / *n, sum[1] / *n, sum[2] / *n).into_color();
+            }
+            None => groups.push((*colour, [l, a, b], 1.0)),
+        }
+    }
+
+    groups.into_iter().map(|(colour, _, _)| colour).collect()
+}
+
+/// Picks `n` colours via farthest-point selection, keeping their original order.
+pub fn farthest_points(palette: &[Srgb], n: usize, metric: DeltaMetric) -> Vec<Srgb> {
+    if palette.len() <= n {
+        return palette.to_vec();
+    }
+
+    let darkest = (0..palette.len())
+        .map(|i| (i, IntoColor::<Lab>::into_color(palette[i]).l))
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(i, _)| i)
+        .unwrap();
+
+    let mut picked = vec![darkest];
+    let mut nearest = palette
+        .iter()
+        .map(|colour| metric.delta(*colour, palette[darkest]))
+        .collect::<Vec<_>>();
+
+    while picked.len() < n {
+        let next = (0..palette.len())
+            .filter(|i| !picked.contains(i))
+            .max_by(|&a, &b| nearest[a].total_cmp(&nearest[b]))
+            .unwrap();
+
+        picked.push(next);
+        for (i, colour) in palette.iter().enumerate() {
+            nearest[i] = nearest[i].min(metric.delta(*colour, palette[next]));
+        }
+    }
+
+    picked.sort();
+    picked.into_iter().map(|i| palette[i]).collect()
+}
+
+fn sort_palette(palette: &mut [Srgb], by: SortBy) {
+    let key = |colour: &Srgb| -> (bool, f32) {
+        let lch: Lch = (*colour).into_color();
+        match by {
+            SortBy::Lightness => (false, lch.l),
+            SortBy::Hue if lch.chroma < GREY_CHROMA => (false, lch.l),
+            SortBy::Hue => (true, lch.hue.into_positive_degrees()),
+        }
+    };
+
+    palette.sort_by(|a, b| {
+        let (a, b) = (key(a), key(b));
+        a.0.cmp(&b.0).then(a.1.total_cmp(&b.1))
+    });
+}
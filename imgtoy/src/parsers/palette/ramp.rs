@@ -0,0 +1,128 @@
+use palette::{IntoColor, Lch, Srgb};
+use rand::{seq::SliceRandom, Rng};
+use serde_yaml::Value;
+
+use crate::{
+    effects::{BaseResult, Log},
+    parsers::util::{parse_property_as_f64_complex, parse_property_as_u64_complex},
+};
+
+use super::{parse_colour, GenerationSpace};
+
+// RAMPS
+// =====
+// A ramp is a pixel-art style set of shades for one colour. Unlike [shades], which only
+//  varies lightness, a ramp shifts hue - towards warm in the highlights, and towards cool in
+//  the shadows - and lets saturation peak in the middle, fading towards either end.
+//
+//      - ramp:
+//          base: { rgb: 3A7D44 }
+//          steps: 6
+//          min-lum: 10
+//          max-lum: 95
+//          hue-shift: 30
+//          chroma-peak: 1.0
+//          chroma-ends: 0.4
+//
+// PARAMETERS:
+// base = the colour the ramp is built around. its lightness is where the ramp "centres".
+// steps = how many shades to generate. (5 by default)
+// min-lum, max-lum = the lightness of the darkest and lightest shades. (10 and 95 by default)
+// hue-shift = how far (in degrees) the ends rotate towards warm / cool. (20 by default)
+//      negative values shift the other way.
+// chroma-peak, chroma-ends = chroma relative to the base colour's, at the base and at
+//      either end of the ramp. (1.0 and 0.5 by default)
+//
+// Shades that fall outside sRGB have their chroma reduced to fit.
+
+/// Roughly where "warm" (orange-yellow) and "cool" (blue) sit in LCH.
+const WARM_HUE: f32 = 70.0;
+const COOL_HUE: f32 = 280.0;
+
+pub fn parse_ramp(log: Log, rng: &mut impl Rng, ramp: &Value) -> BaseResult<Vec<Srgb>> {
+    if !ramp.is_mapping() {
+        return Err("[ramp] must be a mapping.".into());
+    }
+
+    let base = parse_colour(
+        log,
+        rng,
+        ramp.get("base").expect("[ramp.base] is required."),
+    )?;
+    let base = *base
+        .choose(rng)
+        .expect("[ramp.base] must produce at least one colour.");
+
+    let steps = parse_property_as_u64_complex(log, rng, ramp, "steps")?.unwrap_or(5);
+    if steps == 0 {
+        return Err("[ramp.steps] must be at least 1.".into());
+    }
+
+    let min_lum = parse_property_as_f64_complex(log, rng, ramp, "min-lum")?.unwrap_or(10.0);
+    let max_lum = parse_property_as_f64_complex(log, rng, ramp, "max-lum")?.unwrap_or(95.0);
+    let hue_shift = parse_property_as_f64_complex(log, rng, ramp, "hue-shift")?.unwrap_or(20.0);
+    let chroma_peak = parse_property_as_f64_complex(log, rng, ramp, "chroma-peak")?.unwrap_or(1.0);
+    let chroma_ends = parse_property_as_f64_complex(log, rng, ramp, "chroma-ends")?.unwrap_or(0.5);
+
+    Ok(build_ramp(
+        base,
+        steps,
+        (min_lum as f32, max_lum as f32),
+        hue_shift as f32,
+        (chroma_peak as f32, chroma_ends as f32),
+    ))
+}
+
+pub fn build_ramp(
+    base: Srgb,
+    steps: u64,
+    (min_lum, max_lum): (f32, f32),
+    hue_shift: f32,
+    (chroma_peak, chroma_ends): (f32, f32),
+) -> Vec<Srgb> {
+    let base: Lch = base.into_color();
+    let base_hue = base.hue.into_positive_degrees();
+
+    // where the base colour sits along the ramp, from 0 (darkest) to 1 (lightest).
+    let centre = if max_lum > min_lum {
+        ((base.l - min_lum) / (max_lum - min_lum)).clamp(0.0, 1.0)
+    } else {
+        0.5
+    };
+
+    (0..steps)
+        .map(|i| {
+            let t = match steps {
+                1 => centre,
+                _ => i as f32 / (steps - 1) as f32,
+            };
+            let l = min_lum + t * (max_lum - min_lum);
+
+            // how far towards either end of the ramp this shade is, from 0 to 1.
+            let (distance, target) = if t < centre {
+                ((centre - t) / centre, COOL_HUE)
+            } else if t > centre {
+                ((t - centre) / (1.0 - centre), WARM_HUE)
+            } else {
+                (0.0, base_hue)
+            };
+
+            let hue = rotate_towards(base_hue, target, hue_shift * distance);
+            let chroma =
+                base.chroma * (chroma_ends + (chroma_peak - chroma_ends) * (1.0 - distance));
+
+            GenerationSpace::Lch.map_to_srgb(Lch::new(l, chroma.max(0.0), hue))
+        })
+        .collect()
+}
+
+/// Rotates `hue` by up to `amount` degrees towards `target`, without overshooting it.
+fn rotate_towards(hue: f32, target: f32, amount: f32) -> f32 {
+    let diff = (target - hue + 540.0).rem_euclid(360.0) - 180.0;
+    let step = if amount >= 0.0 {
+        amount.min(diff.abs())
+    } else {
+        amount
+    };
+    (hue + step * diff.signum()).rem_euclid(360.0)
+}
@@ -0,0 +1,57 @@
+use image_effects::effect::Effect;
+use serde_yaml::{Mapping, Value};
+
+use crate::{effects::cvd::Deficiency, source::ImageResult};
+
+// ACCESSIBILITY PREVIEWS
+// ======================
+// Each saved result can also be written as it would appear with a colour vision deficiency:
+//
+//      output:
+//        simulate: [protanopia, deuteranopia, tritanopia]   # or just `all`
+//
+// Previews are named after the iteration, e.g. `00003.protanopia.png` - or `.gif` for gifs.
+//
+// Independently of this, "ordered" warns under [palette] whenever two of its colours become
+//  indistinguishable with any of these deficiencies - see [effects::cvd].
+
+fn parse_deficiency(name: &str) -> Deficiency {
+    Deficiency::from_name(name).unwrap_or_else(|| {
+        panic!(
+            "{name} is not a valid simulation - expected protanopia, deuteranopia or tritanopia."
+        )
+    })
+}
+
+/// Reads `output.simulate` - which is a deficiency, a list of deficiencies, or `all`.
+pub fn parse_simulations(output: &Mapping) -> Vec<Deficiency> {
+    match output.get("simulate") {
+        None => vec![],
+        Some(Value::String(name)) if name == "all" => Deficiency::ALL.to_vec(),
+        Some(Value::String(name)) => vec![parse_deficiency(name)],
+        Some(Value::Sequence(names)) => names
+            .iter()
+            .map(|name| {
+                parse_deficiency(
+                    name.as_str()
+                        .expect("[output.simulate] must be a list of deficiencies."),
+                )
+            })
+            .collect(),
+        Some(_) => {
+            panic!("[output.simulate] must be a deficiency, a list of deficiencies, or `all`.")
+        }
+    }
+}
+
+pub fn simulate(deficiency: Deficiency, rendered: &ImageResult) -> ImageResult {
+    match rendered {
+        ImageResult::Image(image) => ImageResult::Image(deficiency.affect(image.clone())),
+        ImageResult::Gif(frames) => ImageResult::Gif(
+            frames
+                .iter()
+                .map(|frame| deficiency.affect(frame.clone()))
+                .collect(),
+        ),
+    }
+}
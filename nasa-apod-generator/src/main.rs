@@ -1,7 +1,7 @@
 use std::{error::Error, time::Duration};
 
 use clokwerk::{AsyncScheduler, TimeUnits};
-use common_utils::{palette::{palettes, generate_palette_html, generate_random_palette}, image::resize_image_with_max_dim};
+use common_utils::{palette::{palettes, generate_palette_html}, naming::describe_palette, image::resize_image_with_max_dim, generator::{load_generator, PaletteGenerator, RandomV1}};
 use image::GenericImageView;
 use image_effects::{prelude::*, dither::bayer::Bayer};
 use nasa::ApodResponse;
@@ -19,6 +19,8 @@ mod utils;
 use crate::nasa::get_random_apod;
 
 const ITERATIONS: usize = 1;
+/// How many distinct colour names the alt text describes the palette with.
+const ALT_TEXT_COLOUR_NAMES: usize = 6;
 
 static mut RUNNABLE: bool = true;
 const SAFEGUARD_SECS: u64 = 60 * 60; // hour
@@ -87,10 +89,15 @@ async fn execute() -> Result<(), Box<dyn Error>> {
 
     if rng.gen_bool(0.25) {
         println!("generating palettes...");
+        // a palette config (as YAML) can be set via PALETTE_CONFIG - random_v1 is used otherwise.
+        let generator: Box<dyn PaletteGenerator> = match std::env::var("PALETTE_CONFIG") {
+            Ok(path) => load_generator(path)?,
+            Err(_) => Box::new(RandomV1),
+        };
         for i in 0..50 {
             _palettes.push((
                 format!("generated({i})"),
-                generate_random_palette(&mut rng).0
+                generator.generate(&mut rng)?
             ));
         }
     } else {
@@ -150,7 +157,10 @@ async fn dispatch_apod_image_to_cohost(
         Some(metadata)
     ).await?;
 
-    let alt_text = format!("Astronomy Photo Of the Day for: {}, dithered using {palette_name}. Titled: {}", response.date, response.title);
+    let alt_text = format!(
+        "Astronomy Photo Of the Day for: {}, dithered using {palette_name} ({}). Titled: {}",
+        response.date, describe_palette(&palette_cols, ALT_TEXT_COLOUR_NAMES), response.title
+    );
     attachment.alt_text = Some(alt_text.clone());
 
     let palette_html = generate_palette_html(palette_cols);